@@ -0,0 +1,104 @@
+use clap::Parser;
+use log::{error, info};
+use resonite_link_client::test_support::{Simulator, World};
+use std::path::PathBuf;
+use tokio::sync::watch;
+
+mod snapshot;
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// The JSON file of the world to serve, an empty world is served without one.
+    #[arg(short, long)]
+    world: Option<PathBuf>,
+
+    /// The address to accept Resolink connections at.
+    ///
+    /// A port of 0 picks any free port, the address is printed once serving.
+    #[arg(short, long, default_value = "127.0.0.1:18229")]
+    addr: String,
+
+    /// Write every change made by a client back to the world file.
+    #[arg(long, requires = "world")]
+    persist: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
+    let args = Args::parse();
+
+    let world = match &args.world {
+        Some(path) => snapshot::load(path)?,
+        None => World::default(),
+    };
+    let mut simulator = Simulator::new(world);
+    let mut writer = None;
+    if let (true, Some(path)) = (args.persist, args.world.clone()) {
+        let (changed, latest) = watch::channel(None);
+        writer = Some(tokio::spawn(persist(path, latest)));
+        // The hook runs with the world locked, so the world is only copied here.
+        simulator = simulator.with_mutation_hook(move |world| {
+            changed.send_replace(Some(world.clone()));
+        });
+    }
+
+    let server = simulator.listen(&args.addr).await?;
+    info!("Serving the world at {}", server.url());
+
+    tokio::signal::ctrl_c().await?;
+    // Dropping every copy of the hook lets the writer finish the last change and stop.
+    drop(server);
+    drop(simulator);
+    if let Some(writer) = writer {
+        writer.await?;
+    }
+    Ok(())
+}
+
+/// Writes the world file in the background, skipping to the latest world
+/// when the world changed more than once during a write.
+async fn persist(path: PathBuf, mut latest: watch::Receiver<Option<World>>) {
+    while latest.changed().await.is_ok() {
+        let Some(world) = latest.borrow_and_update().clone() else {
+            continue;
+        };
+        let path = path.clone();
+        match tokio::task::spawn_blocking(move || snapshot::save(&path, &world)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => error!("Failed to persist the world: {}", e),
+            Err(e) => error!("Failed to persist the world: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use resonite_link_client::Message;
+    use resonite_link_client::data_model::SlotPatch;
+
+    #[tokio::test]
+    async fn persists_the_latest_world() {
+        let path =
+            std::env::temp_dir().join(format!("resolink-sim-persist-{}.json", std::process::id()));
+        let (changed, latest) = watch::channel(None);
+        let writer = tokio::spawn(persist(path.clone(), latest));
+
+        let mut world = World::default();
+        changed.send_replace(Some(world.clone()));
+        world
+            .handle(Message::UpdateSlot {
+                data: SlotPatch::new("Root").tag("Taco"),
+            })
+            .unwrap();
+        changed.send_replace(Some(world.clone()));
+        drop(changed);
+        writer.await.unwrap();
+
+        let saved = snapshot::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved.root(), world.root());
+    }
+}
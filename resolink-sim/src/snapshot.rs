@@ -0,0 +1,82 @@
+use resonite_link_client::Response;
+use resonite_link_client::data_model::Slot;
+use resonite_link_client::responses::ResponseKind;
+use resonite_link_client::test_support::World;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("failed to access the world file {0}")]
+    Io(#[from] std::io::Error),
+    #[error("the world file is not a slot or a slotData response {0}")]
+    Invalid(serde_json::Error),
+}
+
+/// Reads a world from the JSON of its root slot, or of a `slotData` response.
+pub fn parse(json: &str) -> Result<World, SnapshotError> {
+    let root = match serde_json::from_str::<Slot>(json) {
+        Ok(root) => root,
+        Err(e) => match serde_json::from_str::<Response>(json) {
+            Ok(Response {
+                kind: ResponseKind::SlotData {
+                    data: Some(root), ..
+                },
+                ..
+            }) => root,
+            _ => return Err(SnapshotError::Invalid(e)),
+        },
+    };
+    Ok(World::new(root))
+}
+
+pub fn load(path: &Path) -> Result<World, SnapshotError> {
+    parse(&std::fs::read_to_string(path)?)
+}
+
+/// Writes the root slot of the world, replacing the file only once it is fully written.
+pub fn save(path: &Path, world: &World) -> Result<(), SnapshotError> {
+    let json = serde_json::to_string_pretty(world.root()).map_err(SnapshotError::Invalid)?;
+    let temp = path.with_extension("tmp");
+    std::fs::write(&temp, json)?;
+    std::fs::rename(temp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_slot_data_responses() {
+        let root = serde_json::to_value(World::default().root()).unwrap();
+        let response = serde_json::json!({
+            "$type": "slotData",
+            "depth": -1,
+            "data": root,
+            "sourceMessageId": "Taco",
+            "success": true,
+            "errorInfo": null,
+        });
+
+        let world = parse(&response.to_string()).unwrap();
+        assert_eq!(world.root(), World::default().root());
+    }
+
+    #[test]
+    fn rejects_other_json() {
+        assert!(matches!(
+            parse("{ \"taco\": true }"),
+            Err(SnapshotError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let path = std::env::temp_dir().join(format!("resolink-sim-{}.json", std::process::id()));
+        save(&path, &World::default()).unwrap();
+        let world = load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(world.root(), World::default().root());
+    }
+}
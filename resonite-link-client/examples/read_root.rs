@@ -15,14 +15,7 @@ async fn main() {
         .await
         .unwrap();
 
-    let response = client
-        .send(resonite_link_client::Message::GetSlot {
-            slot_id: "Root".into(),
-            depth: 3,
-            include_component_data: true,
-        })
-        .await
-        .unwrap();
+    let root = client.get_slot("Root", 3, true).await.unwrap();
 
-    println!("{:#?}", response);
+    println!("{:#?}", root);
 }
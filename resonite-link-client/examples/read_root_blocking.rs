@@ -0,0 +1,18 @@
+fn main() {
+    env_logger::init();
+
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 2 {
+        println!("The first argument must be the url to connect to.");
+        return;
+    }
+
+    let address = args[1].clone();
+
+    let client = resonite_link_client::BlockingClient::connect(&address, None).unwrap();
+
+    let root = client.get_slot("Root", 3, true).unwrap();
+
+    println!("{:#?}", root);
+}
@@ -0,0 +1,271 @@
+//! A synchronous client, for programs that do not otherwise use async.
+//!
+//! Requires the `blocking` feature.
+
+use crate::controller::{
+    Client, ClientConfig, ClientError, ConnectionEvent, Connector, OutOfBandMessage, QueueDepth,
+    RateLimitStats, ShutdownHandle, Transport,
+};
+use crate::data_model::{Component, ComponentPatch, Slot, SlotPatch};
+use crate::messages::Message;
+use crate::requests::Request;
+use crate::responses::{ParseDiagnostic, Response};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite;
+
+/// A [`Client`] that blocks the calling thread instead of returning futures.
+///
+/// The client owns a runtime with a single worker thread, which keeps serving the connection
+/// between calls. Clones share the connection and the runtime.
+///
+/// The methods must not be called from within an async runtime, they panic if they are.
+/// Async code should use [`Client`] directly.
+#[derive(Clone)]
+pub struct BlockingClient {
+    // Dropping without closing stops serving the connection along with the runtime,
+    // without the closing handshake.
+    client: Client,
+    runtime: Arc<Runtime>,
+}
+
+impl BlockingClient {
+    pub fn connect(address: &str, id_prefix: Option<&str>) -> Result<Self, ClientError> {
+        Self::start(|| Client::connect(address, id_prefix))
+    }
+
+    pub fn connect_with_config(address: &str, config: ClientConfig) -> Result<Self, ClientError> {
+        Self::start(|| Client::connect_with_config(address, config))
+    }
+
+    /// Connects using a custom connector, see [`Client::connect_with`].
+    pub fn connect_with(
+        connector: impl Connector,
+        config: ClientConfig,
+    ) -> Result<Self, ClientError> {
+        Self::start(|| Client::connect_with(connector, config))
+    }
+
+    /// Runs the client over an already established transport, see [`Client::with_transport`].
+    pub fn with_transport(
+        transport: impl Transport,
+        config: ClientConfig,
+    ) -> Result<Self, ClientError> {
+        Self::start(|| async { Ok(Client::with_transport(transport, config)) })
+    }
+
+    fn start<F: Future<Output = Result<Client, ClientError>>>(
+        connect: impl FnOnce() -> F,
+    ) -> Result<Self, ClientError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .map_err(|e| ClientError::FailureToConnect(tungstenite::Error::Io(e)))?;
+        let client = runtime.block_on(connect())?;
+        Ok(Self {
+            client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The async client sharing this connection, which can be used from async code.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Sends a message and waits for the response, see [`Client::send`].
+    pub fn send(&self, message: Message) -> Result<Response, ClientError> {
+        self.runtime.block_on(self.client.send(message))
+    }
+
+    /// Sends a message with a chosen `messageId`, see [`Client::send_with_id`].
+    pub fn send_with_id(
+        &self,
+        id: impl Into<String>,
+        message: Message,
+    ) -> Result<Response, ClientError> {
+        self.runtime.block_on(self.client.send_with_id(id, message))
+    }
+
+    /// Sends a message with a chosen `messageId` overriding the default timeout,
+    /// see [`Client::send_with_id_and_timeout`].
+    pub fn send_with_id_and_timeout(
+        &self,
+        id: impl Into<String>,
+        message: Message,
+        timeout: Option<Duration>,
+    ) -> Result<Response, ClientError> {
+        self.runtime
+            .block_on(self.client.send_with_id_and_timeout(id, message, timeout))
+    }
+
+    /// Sends a message overriding the default timeout, see [`Client::send_with_timeout`].
+    pub fn send_with_timeout(
+        &self,
+        message: Message,
+        timeout: Option<Duration>,
+    ) -> Result<Response, ClientError> {
+        self.runtime
+            .block_on(self.client.send_with_timeout(message, timeout))
+    }
+
+    /// Sends a typed request, see [`Client::request`].
+    pub fn request<R: Request>(&self, request: R) -> Result<R::Output, ClientError> {
+        self.runtime.block_on(self.client.request(request))
+    }
+
+    /// Sends a typed request overriding the default timeout, see [`Client::request_with_timeout`].
+    pub fn request_with_timeout<R: Request>(
+        &self,
+        request: R,
+        timeout: Option<Duration>,
+    ) -> Result<R::Output, ClientError> {
+        self.runtime
+            .block_on(self.client.request_with_timeout(request, timeout))
+    }
+
+    /// Sends a typed request, also returning the parse diagnostics,
+    /// see [`Client::request_with_diagnostics`].
+    pub fn request_with_diagnostics<R: Request>(
+        &self,
+        request: R,
+        timeout: Option<Duration>,
+    ) -> Result<(R::Output, Vec<ParseDiagnostic>), ClientError> {
+        self.runtime
+            .block_on(self.client.request_with_diagnostics(request, timeout))
+    }
+
+    pub fn get_slot(
+        &self,
+        slot_id: impl Into<String>,
+        depth: i32,
+        include_component_data: bool,
+    ) -> Result<Slot, ClientError> {
+        self.runtime
+            .block_on(self.client.get_slot(slot_id, depth, include_component_data))
+    }
+
+    pub fn add_slot(&self, slot: Slot) -> Result<Slot, ClientError> {
+        self.runtime.block_on(self.client.add_slot(slot))
+    }
+
+    /// Changes the fields set in the patch, see [`Client::update_slot`].
+    pub fn update_slot(&self, patch: impl Into<SlotPatch>) -> Result<Slot, ClientError> {
+        self.runtime.block_on(self.client.update_slot(patch))
+    }
+
+    pub fn remove_slot(&self, slot_id: impl Into<String>) -> Result<(), ClientError> {
+        self.runtime.block_on(self.client.remove_slot(slot_id))
+    }
+
+    pub fn get_component(&self, component_id: impl Into<String>) -> Result<Component, ClientError> {
+        self.runtime
+            .block_on(self.client.get_component(component_id))
+    }
+
+    pub fn add_component(
+        &self,
+        container_slot_id: impl Into<String>,
+        component: Component,
+    ) -> Result<Component, ClientError> {
+        self.runtime
+            .block_on(self.client.add_component(container_slot_id, component))
+    }
+
+    /// Changes the members in the patch, see [`Client::update_component`].
+    pub fn update_component(
+        &self,
+        patch: impl Into<ComponentPatch>,
+    ) -> Result<Component, ClientError> {
+        self.runtime.block_on(self.client.update_component(patch))
+    }
+
+    pub fn remove_component(&self, component_id: impl Into<String>) -> Result<(), ClientError> {
+        self.runtime
+            .block_on(self.client.remove_component(component_id))
+    }
+
+    pub fn queue_depth(&self) -> QueueDepth {
+        self.client.queue_depth()
+    }
+
+    pub fn rate_limit_stats(&self) -> RateLimitStats {
+        self.client.rate_limit_stats()
+    }
+
+    pub fn default_timeout(&self) -> Option<Duration> {
+        self.client.default_timeout()
+    }
+
+    /// Sets the timeout used by [`BlockingClient::send`] on this handle, `None` disables the timeout.
+    pub fn set_default_timeout(&mut self, timeout: Option<Duration>) {
+        self.client.set_default_timeout(timeout);
+    }
+
+    /// Subscribes to connection events, which can be waited for with
+    /// [`broadcast::Receiver::blocking_recv`].
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.client.subscribe_events()
+    }
+
+    /// Subscribes to out of band messages, see [`Client::subscribe_out_of_band`].
+    pub fn subscribe_out_of_band(&self) -> broadcast::Receiver<OutOfBandMessage> {
+        self.client.subscribe_out_of_band()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.client.is_closed()
+    }
+
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.client.shutdown_handle()
+    }
+
+    /// Closes the connection for every handle, see [`Client::close`].
+    pub fn close(self) -> Result<(), ClientError> {
+        self.runtime.block_on(self.client.close())
+    }
+
+    /// Closes the connection once pending requests are answered, see [`Client::drain`].
+    pub fn drain(self, timeout: Duration) -> Result<(), ClientError> {
+        self.runtime.block_on(self.client.drain(timeout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_model::Field;
+    use crate::test_support::Simulator;
+
+    #[test]
+    fn edits_slots_without_async() {
+        let server_runtime = Runtime::new().unwrap();
+        let simulator = Simulator::default();
+        let server = server_runtime
+            .block_on(simulator.listen("127.0.0.1:0"))
+            .unwrap();
+
+        let client = BlockingClient::connect(&server.url(), None).unwrap();
+        let added = client
+            .add_slot(Slot {
+                name: Field::new("", Some("Taco".into())),
+                ..Default::default()
+            })
+            .unwrap();
+        let root = client.get_slot("Root", 1, false).unwrap();
+        assert_eq!(root.children, vec![added]);
+
+        let other = client.clone();
+        client.close().unwrap();
+        assert!(other.is_closed());
+        assert!(matches!(
+            other.get_slot("Root", 0, false),
+            Err(ClientError::ConnectionClosed)
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::controller::ClientError;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// What [`Client::send`](crate::Client::send) does when the pending request limit is reached.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Wait until an earlier request completes, the wait counts towards the request timeout.
+    #[default]
+    Wait,
+    /// Fail immediately with [`ClientError::QueueFull`].
+    FailFast,
+}
+
+/// A snapshot of how many requests a client is handling.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueueDepth {
+    /// Requests waiting to be written to the connection.
+    pub queued: usize,
+    /// Requests written to the connection that are waiting for a response.
+    pub in_flight: usize,
+}
+
+/// Limits how many requests may be queued or in flight at once.
+pub(crate) struct RequestLimiter {
+    permits: Arc<Semaphore>,
+    limit: usize,
+    backpressure: Backpressure,
+}
+
+impl RequestLimiter {
+    pub fn new(limit: Option<usize>, backpressure: Backpressure) -> Self {
+        // A limit of 0 would never let a request through, and the semaphore panics over its maximum.
+        let limit = limit
+            .unwrap_or(Semaphore::MAX_PERMITS)
+            .clamp(1, Semaphore::MAX_PERMITS);
+        Self {
+            permits: Arc::new(Semaphore::new(limit)),
+            limit,
+            backpressure,
+        }
+    }
+
+    /// Reserves room for one request, the room is released when the permit is dropped.
+    pub async fn acquire(&self) -> Result<OwnedSemaphorePermit, ClientError> {
+        match self.backpressure {
+            Backpressure::Wait => self
+                .permits
+                .clone()
+                .acquire_owned()
+                .await
+                .map_err(|_| ClientError::ConnectionClosed),
+            Backpressure::FailFast => self
+                .permits
+                .clone()
+                .try_acquire_owned()
+                .map_err(|_| ClientError::QueueFull),
+        }
+    }
+
+    /// Requests that currently hold a permit.
+    pub fn pending(&self) -> usize {
+        self.limit - self.permits.available_permits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn fail_fast_rejects_over_limit() {
+        let limiter = RequestLimiter::new(Some(1), Backpressure::FailFast);
+        let permit = limiter.acquire().await.unwrap();
+        assert_eq!(limiter.pending(), 1);
+        assert!(matches!(
+            limiter.acquire().await,
+            Err(ClientError::QueueFull)
+        ));
+
+        drop(permit);
+        assert_eq!(limiter.pending(), 0);
+        assert!(limiter.acquire().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn wait_resumes_when_room_is_freed() {
+        let limiter = Arc::new(RequestLimiter::new(Some(1), Backpressure::Wait));
+        let permit = limiter.acquire().await.unwrap();
+
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire().await.map(drop) }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        drop(permit);
+        assert!(waiting.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn zero_limit_still_allows_one_request() {
+        let limiter = RequestLimiter::new(Some(0), Backpressure::Wait);
+        let _permit = tokio::time::timeout(Duration::from_secs(1), limiter.acquire())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(limiter.pending(), 1);
+    }
+
+    #[tokio::test]
+    async fn huge_limit_is_lowered_to_the_maximum() {
+        let limiter = RequestLimiter::new(Some(usize::MAX), Backpressure::FailFast);
+        let _permit = limiter.acquire().await.unwrap();
+        assert_eq!(limiter.pending(), 1);
+    }
+
+    #[tokio::test]
+    async fn unlimited_counts_pending() {
+        let limiter = RequestLimiter::new(None, Backpressure::FailFast);
+        let _first = limiter.acquire().await.unwrap();
+        let _second = limiter.acquire().await.unwrap();
+        assert_eq!(limiter.pending(), 2);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::controller::{Client, ClientConfig, ClientError, WebSocketConnector};
+use std::time::Duration;
+
+#[cfg(feature = "rustls")]
+use rustls::pki_types::CertificateDer;
+
+/// Connects a [`Client`] with custom websocket settings, see [`Client::builder`].
+///
+/// The settings are kept by a [`WebSocketConnector`], so they also apply when reconnecting.
+/// permessage-deflate is not offered, as tungstenite cannot read compressed frames.
+#[derive(Clone, Debug)]
+pub struct ClientBuilder {
+    connector: WebSocketConnector,
+    config: ClientConfig,
+}
+
+impl ClientBuilder {
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            connector: WebSocketConnector::new(address),
+            config: ClientConfig::default(),
+        }
+    }
+
+    /// The settings for the client itself, replacing any set before.
+    pub fn config(mut self, config: ClientConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// See [`WebSocketConnector::header`].
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.connector = self.connector.header(name, value);
+        self
+    }
+
+    /// See [`WebSocketConnector::max_frame_size`].
+    pub fn max_frame_size(mut self, size: Option<usize>) -> Self {
+        self.connector = self.connector.max_frame_size(size);
+        self
+    }
+
+    /// See [`WebSocketConnector::max_message_size`].
+    pub fn max_message_size(mut self, size: Option<usize>) -> Self {
+        self.connector = self.connector.max_message_size(size);
+        self
+    }
+
+    /// See [`WebSocketConnector::nodelay`].
+    pub fn nodelay(mut self, nodelay: bool) -> Self {
+        self.connector = self.connector.nodelay(nodelay);
+        self
+    }
+
+    /// See [`WebSocketConnector::connect_timeout`].
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connector = self.connector.connect_timeout(timeout);
+        self
+    }
+
+    /// See [`WebSocketConnector::add_root_certificate`].
+    #[cfg(feature = "rustls")]
+    pub fn add_root_certificate(mut self, certificate: CertificateDer<'static>) -> Self {
+        self.connector = self.connector.add_root_certificate(certificate);
+        self
+    }
+
+    /// See [`WebSocketConnector::add_root_certificates_pem`].
+    #[cfg(feature = "rustls")]
+    pub fn add_root_certificates_pem(
+        mut self,
+        pem: &[u8],
+    ) -> Result<Self, rustls::pki_types::pem::Error> {
+        self.connector = self.connector.add_root_certificates_pem(pem)?;
+        Ok(self)
+    }
+
+    /// The configured connector and client settings, to connect through
+    /// [`Client::connect_with`] after wrapping the connector, for example with a
+    /// [`Recorder`](crate::recording::Recorder).
+    pub fn into_parts(self) -> (WebSocketConnector, ClientConfig) {
+        (self.connector, self.config)
+    }
+
+    pub async fn connect(self) -> Result<Client, ClientError> {
+        Client::connect_with(self.connector, self.config).await
+    }
+}
+
+impl Client {
+    /// Starts configuring a connection to the address.
+    pub fn builder(address: impl Into<String>) -> ClientBuilder {
+        ClientBuilder::new(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use tokio::net::TcpListener;
+    use tokio::sync::oneshot;
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+
+    #[tokio::test]
+    // The handshake callback's signature is chosen by tungstenite.
+    #[allow(clippy::result_large_err)]
+    async fn sends_custom_headers() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("ws://{}", listener.local_addr().unwrap());
+        let (header_sender, header) = oneshot::channel();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_hdr_async(
+                stream,
+                |request: &Request, response: Response| {
+                    let value = request.headers().get("X-Taco").cloned();
+                    _ = header_sender.send(value);
+                    Ok(response)
+                },
+            )
+            .await
+            .unwrap();
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let client = Client::builder(address)
+            .header("X-Taco", "Carnitas")
+            .nodelay(true)
+            .connect()
+            .await
+            .unwrap();
+
+        assert_eq!(header.await.unwrap().unwrap(), "Carnitas");
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_headers() {
+        let result = Client::builder("ws://127.0.0.1:1")
+            .header("Not a header", "Taco")
+            .connect()
+            .await;
+        assert!(matches!(result, Err(ClientError::FailureToConnect(_))));
+    }
+
+    #[tokio::test]
+    async fn times_out_connecting() {
+        // Accepts the connection, but never answers the handshake.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("ws://{}", listener.local_addr().unwrap());
+
+        let result = Client::builder(address)
+            .connect_timeout(Duration::from_millis(50))
+            .connect()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ClientError::FailureToConnect(tokio_tungstenite::tungstenite::Error::Io(e)))
+                if e.kind() == std::io::ErrorKind::TimedOut
+        ));
+        drop(listener);
+    }
+}
@@ -1,66 +1,378 @@
-use crate::controller::id_generator::IdGenerator;
+use crate::controller::backpressure::{QueueDepth, RequestLimiter};
+use crate::controller::config::{ClientConfig, ParseMode};
+use crate::controller::events::{ConnectionEvent, ConnectionLoss, DisconnectReason};
+use crate::controller::id_generator::{IdStrategy, SequentialIds};
+use crate::controller::out_of_band::OutOfBandMessage;
+use crate::controller::rate_limit::{RateLimitStats, RateLimiter};
+use crate::controller::reconnect::{OutageBehavior, ReconnectPolicy};
+use crate::controller::remote_error::RemoteError;
+use crate::controller::transport::{BoxTransport, Connector, Transport, WebSocketConnector};
 use crate::messages::{Message, MessageWrapper};
-use crate::responses::{FallbackResponse, Response};
+use crate::responses::{FallbackResponse, ParseError, Response, ResponseKind};
+use futures_util::stream::SplitStream;
 use futures_util::{Sink, SinkExt, Stream, StreamExt};
-use log::{error, warn};
-use std::collections::HashMap;
+use log::{error, info, warn};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::{Mutex, mpsc};
-use tokio::sync::{SetOnce, oneshot};
+use tokio::sync::{OwnedSemaphorePermit, SetOnce, oneshot};
+use tokio::sync::{broadcast, mpsc};
 use tokio::task::JoinHandle;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
 
+/// A handle to a connection, clones are cheap and share the connection.
+/// The connection is closed when the last handle is dropped, or by [`Client::close`].
+#[derive(Clone)]
 pub struct Client {
+    inner: Arc<ClientInner>,
+    default_timeout: Option<Duration>,
+}
+
+struct ClientInner {
     outbound: mpsc::UnboundedSender<OutboundMessage>,
+    in_flight_messages: InFlightMessages,
+    pending_ids: PendingIds,
+    limiter: RequestLimiter,
+    rate_limiter: Arc<RateLimiter>,
+    message_ids: Arc<dyn IdStrategy>,
+    reconnect_policy: Option<ReconnectPolicy>,
+    events: broadcast::Sender<ConnectionEvent>,
+    out_of_band: broadcast::Sender<OutOfBandMessage>,
     shutdown: Arc<SetOnce<()>>,
-    handle: JoinHandle<()>,
+    /// When a drain has to give up on the remaining requests.
+    drain: Arc<SetOnce<Instant>>,
+    task: tokio::sync::Mutex<Task>,
+}
+
+/// The background task serving the connection.
+enum Task {
+    Running(JoinHandle<()>),
+    /// The task ended, with the reason if it panicked or was cancelled.
+    Finished(Option<String>),
+}
+
+impl Drop for ClientInner {
+    fn drop(&mut self) {
+        // Also stops a reconnection that is holding requests, which would never see the drop.
+        _ = self.shutdown.set(());
+    }
 }
 
+/// Closes a client's connection without keeping the connection alive,
+/// see [`Client::shutdown_handle`].
+#[derive(Clone)]
+pub struct ShutdownHandle {
+    shutdown: Arc<SetOnce<()>>,
+    drain: Arc<SetOnce<Instant>>,
+}
+
+impl ShutdownHandle {
+    /// Starts closing the connection, every client handle fails later requests.
+    pub fn shutdown(&self) {
+        // The connection may have already closed itself.
+        _ = self.shutdown.set(());
+    }
+
+    /// Starts draining the connection, see [`Client::drain`].
+    pub fn drain(&self, timeout: Duration) {
+        // An earlier drain keeps its deadline.
+        _ = self.drain.set(Instant::now() + timeout);
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.shutdown.initialized() || self.drain.initialized()
+    }
+}
+
+/// How many connection events are buffered for each subscriber before it starts lagging.
+const EVENT_CAPACITY: usize = 64;
+
+/// How many out of band messages are buffered for each subscriber before it starts lagging.
+const OUT_OF_BAND_CAPACITY: usize = 64;
+
 impl Client {
+    /// Sends a message and waits for the response, using the client's default timeout.
     pub async fn send(&self, message: Message) -> Result<Response, ClientError> {
+        self.send_with_timeout(message, self.default_timeout).await
+    }
+
+    /// Sends a message with a chosen `messageId` instead of a generated one,
+    /// for example to correlate it with the caller's own logs.
+    ///
+    /// Fails with [`ClientError::DuplicateMessageId`] if a request with the same id
+    /// is still waiting for its response.
+    pub async fn send_with_id(
+        &self,
+        id: impl Into<String>,
+        message: Message,
+    ) -> Result<Response, ClientError> {
+        self.send_timed(message, Some(id.into()), self.default_timeout).await
+    }
+
+    /// Sends a message with a chosen `messageId`, overriding the default timeout,
+    /// see [`Client::send_with_id`] and [`Client::send_with_timeout`].
+    pub async fn send_with_id_and_timeout(
+        &self,
+        id: impl Into<String>,
+        message: Message,
+        timeout: Option<Duration>,
+    ) -> Result<Response, ClientError> {
+        self.send_timed(message, Some(id.into()), timeout).await
+    }
+
+    /// Sends a message and waits for the response, overriding the default timeout.
+    /// A timeout of `None` waits until the response arrives or the connection closes.
+    ///
+    /// If the returned future is dropped or times out the request is abandoned,
+    /// and a response that arrives later is discarded.
+    ///
+    /// A response with `success: false` fails with [`ClientError::Remote`].
+    pub async fn send_with_timeout(
+        &self,
+        message: Message,
+        timeout: Option<Duration>,
+    ) -> Result<Response, ClientError> {
+        self.send_timed(message, None, timeout).await
+    }
+
+    /// Sends a message with the id generated if `None`, giving up after the timeout.
+    async fn send_timed(
+        &self,
+        message: Message,
+        id: Option<String>,
+        timeout: Option<Duration>,
+    ) -> Result<Response, ClientError> {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.dispatch(message, id))
+                .await
+                .map_err(|_| ClientError::Timeout(timeout))?,
+            None => self.dispatch(message, id).await,
+        }
+    }
+
+    async fn dispatch(
+        &self,
+        message: Message,
+        id: Option<String>,
+    ) -> Result<Response, ClientError> {
+        // Checked before and after waiting for room, so requests made while draining
+        // fail right away instead of waiting for room that is never used.
+        if self.inner.drain.initialized() {
+            return Err(ClientError::ConnectionClosed);
+        }
+        let permit = self.inner.limiter.acquire().await?;
+        if self.inner.drain.initialized() {
+            return Err(ClientError::ConnectionClosed);
+        }
+        let request_kind = message.kind();
+        let id = id.unwrap_or_else(|| self.inner.message_ids.next_id());
+        if !self.inner.pending_ids.lock().unwrap().insert(id.clone()) {
+            return Err(ClientError::DuplicateMessageId(id));
+        }
         let (tx, rx) = oneshot::channel::<Result<Response, ClientError>>();
+        let pending = PendingMessage {
+            id: id.clone(),
+            response: rx,
+            in_flight_messages: self.inner.in_flight_messages.clone(),
+            pending_ids: self.inner.pending_ids.clone(),
+            _permit: permit,
+        };
         let outbound_message = OutboundMessage {
+            id,
             message,
             response: tx,
         };
-        self.outbound
+        self.inner
+            .outbound
             .send(outbound_message)
             .map_err(|_| ClientError::ConnectionClosed)?;
-        // The end ? unwraps the channel error, the inner result is directly returned.
-        rx.await.map_err(|_| ClientError::ConnectionClosed)?
+
+        let response = pending.wait().await?;
+        if !response.success {
+            return Err(ClientError::Remote(RemoteError::new(
+                response,
+                request_kind,
+            )));
+        }
+        Ok(response)
+    }
+
+    /// How many requests are waiting to be sent, and how many are waiting for a response.
+    pub fn queue_depth(&self) -> QueueDepth {
+        let in_flight = self.inner.in_flight_messages.lock().unwrap().len();
+        QueueDepth {
+            queued: self.inner.limiter.pending().saturating_sub(in_flight),
+            in_flight,
+        }
+    }
+
+    /// How long messages waited for the rate limits, see [`RateLimits`](crate::RateLimits).
+    pub fn rate_limit_stats(&self) -> RateLimitStats {
+        self.inner.rate_limiter.stats()
+    }
+
+    pub fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout
+    }
+
+    /// Sets the timeout used by [`Client::send`] on this handle, `None` disables the timeout.
+    /// Clones made afterwards start with the same timeout.
+    pub fn set_default_timeout(&mut self, timeout: Option<Duration>) {
+        self.default_timeout = timeout;
+    }
+
+    /// Subscribes to connection events, only events after subscribing are received.
+    /// A subscriber that falls behind skips the oldest events, see [`broadcast::Receiver`].
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.inner.events.subscribe()
+    }
+
+    /// Subscribes to messages from the server that do not answer any request,
+    /// only messages after subscribing are received.
+    /// These are also reported as [`ConnectionEvent::UnpairedResponse`] or
+    /// [`ConnectionEvent::ParseFailure`], but with the message contents.
+    pub fn subscribe_out_of_band(&self) -> broadcast::Receiver<OutOfBandMessage> {
+        self.inner.out_of_band.subscribe()
+    }
+
+    /// How the client reconnects, `None` if it closes once the connection is lost.
+    pub fn reconnect_policy(&self) -> Option<&ReconnectPolicy> {
+        self.inner.reconnect_policy.as_ref()
     }
 
+    /// Whether the connection is closed or closing, including while it is draining.
     pub fn is_closed(&self) -> bool {
-        self.shutdown.initialized()
+        self.inner.shutdown.initialized() || self.inner.drain.initialized()
     }
 
-    pub async fn close(self) {
-        self.shutdown.set(()).unwrap();
-        self.handle.await.unwrap();
+    /// A handle that can close the connection, without keeping it open like a client handle does.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        ShutdownHandle {
+            shutdown: self.inner.shutdown.clone(),
+            drain: self.inner.drain.clone(),
+        }
+    }
+
+    /// Closes the connection for every handle, and waits for it to finish closing.
+    /// Requests that are still queued or waiting for a response fail with
+    /// [`ClientError::ConnectionClosed`].
+    /// Fails with [`ClientError::TaskFailed`] if the task serving the connection panicked.
+    pub async fn close(self) -> Result<(), ClientError> {
+        // The connection may have already closed itself.
+        _ = self.inner.shutdown.set(());
+        self.join_task().await
+    }
+
+    /// Closes the connection for every handle once the requests already made are answered.
+    ///
+    /// New requests fail with [`ClientError::ConnectionClosed`] right away, while queued
+    /// requests are still sent and their responses awaited. Requests that are not answered
+    /// within the timeout fail with [`ClientError::ConnectionClosed`] as the connection closes.
+    /// The connection is not reopened if it is lost while draining.
+    pub async fn drain(self, timeout: Duration) -> Result<(), ClientError> {
+        // An earlier drain keeps its deadline.
+        _ = self.inner.drain.set(Instant::now() + timeout);
+        self.join_task().await
+    }
+
+    async fn join_task(&self) -> Result<(), ClientError> {
+        let mut task = self.inner.task.lock().await;
+        if let Task::Running(handle) = &mut *task {
+            *task = Task::Finished(handle.await.err().map(|e| e.to_string()));
+        }
+        match &*task {
+            Task::Finished(Some(failure)) => Err(ClientError::TaskFailed(failure.clone())),
+            _ => Ok(()),
+        }
     }
 
     pub async fn connect(address: &str, id_prefix: Option<&str>) -> Result<Self, ClientError> {
+        Self::connect_with_config(
+            address,
+            ClientConfig {
+                message_ids: match id_prefix {
+                    Some(prefix) => Arc::new(SequentialIds::new(prefix)),
+                    None => Arc::new(SequentialIds::default()),
+                },
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    pub async fn connect_with_config(
+        address: &str,
+        config: ClientConfig,
+    ) -> Result<Self, ClientError> {
+        Self::connect_with(WebSocketConnector::new(address), config).await
+    }
+
+    /// Connects using a custom connector, which is also used to reconnect if the config allows it.
+    pub async fn connect_with(
+        connector: impl Connector,
+        config: ClientConfig,
+    ) -> Result<Self, ClientError> {
+        let transport = connector.connect().await?;
+        let reconnect = config
+            .reconnect
+            .clone()
+            .map(|policy| (Box::new(connector) as Box<dyn Connector>, policy));
+        Ok(Self::start(transport, config, reconnect))
+    }
+
+    /// Runs the client over an already established transport.
+    /// There is no way to reopen the transport, so the reconnect policy of the config is ignored.
+    pub fn with_transport(transport: impl Transport, config: ClientConfig) -> Self {
+        Self::start(Box::pin(transport), config, None)
+    }
+
+    fn start(
+        transport: BoxTransport,
+        config: ClientConfig,
+        reconnect: Option<(Box<dyn Connector>, ReconnectPolicy)>,
+    ) -> Self {
         let (msg_sender, msg_recv) = mpsc::unbounded_channel::<OutboundMessage>();
         let set_once: Arc<SetOnce<()>> = Default::default();
-
-        let (client, _) = connect_async(address)
-            .await
-            .map_err(ClientError::FailureToConnect)?;
+        let drain: Arc<SetOnce<Instant>> = Default::default();
+        let in_flight_messages: InFlightMessages = Default::default();
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let (out_of_band, _) = broadcast::channel(OUT_OF_BAND_CAPACITY);
+        let rate_limiter = Arc::new(RateLimiter::new(config.rate_limits));
+        let reconnect_policy = reconnect.as_ref().map(|(_, policy)| policy.clone());
 
         let handle = tokio::spawn(serve_connection(
             msg_recv,
             set_once.clone(),
-            client,
-            id_prefix.map(Into::into),
+            drain.clone(),
+            transport,
+            in_flight_messages.clone(),
+            rate_limiter.clone(),
+            events.clone(),
+            out_of_band.clone(),
+            config.parse_mode,
+            reconnect,
         ));
 
-        Ok(Self {
-            outbound: msg_sender,
-            shutdown: set_once,
-            handle,
-        })
+        Self {
+            inner: Arc::new(ClientInner {
+                outbound: msg_sender,
+                in_flight_messages,
+                pending_ids: Default::default(),
+                limiter: RequestLimiter::new(config.max_pending_requests, config.backpressure),
+                rate_limiter,
+                message_ids: config.message_ids,
+                reconnect_policy,
+                events,
+                out_of_band,
+                shutdown: set_once,
+                drain,
+                task: tokio::sync::Mutex::new(Task::Running(handle)),
+            }),
+            default_timeout: config.default_timeout,
+        }
     }
 }
 
@@ -70,66 +382,389 @@ pub enum ClientError {
     FailureToConnect(tokio_tungstenite::tungstenite::Error),
     #[error("socket connection is closed")]
     ConnectionClosed,
+    #[error("connection to the server was lost before a response was received, {0}")]
+    ConnectionLost(ConnectionLoss),
+    #[error("the client is reconnecting to the server")]
+    Disconnected,
     #[error("outbound message is invalid {0}")]
     MessageRenderingError(serde_json::Error),
     #[error("inbound message is invalid {0}")]
-    MessageParsingError(serde_json::Error),
+    MessageParsingError(ParseError),
+    #[error("no response was received within {0:?}")]
+    Timeout(Duration),
+    #[error("too many requests are already pending")]
+    QueueFull,
+    #[error("a request with the message id {0} is already pending")]
+    DuplicateMessageId(String),
+    #[error("expected a {expected} response, but received {received:?}")]
+    UnexpectedResponse {
+        expected: &'static str,
+        received: Box<ResponseKind>,
+    },
+    #[error("the server reported a failure: {0}")]
+    Remote(RemoteError),
+    #[error("the task serving the connection failed {0}")]
+    TaskFailed(String),
 }
 
 type Responder = oneshot::Sender<Result<Response, ClientError>>;
+type InFlightMessages = Arc<Mutex<HashMap<String, Responder>>>;
+/// The ids of every request that has not been answered or abandoned yet, whether sent or queued.
+type PendingIds = Arc<Mutex<HashSet<String>>>;
 
 struct OutboundMessage {
+    id: String,
     message: Message,
     response: Responder,
 }
 
+/// The caller's side of a request, removes the in flight entry when dropped
+/// so abandoned requests do not leak.
+struct PendingMessage {
+    id: String,
+    response: oneshot::Receiver<Result<Response, ClientError>>,
+    in_flight_messages: InFlightMessages,
+    pending_ids: PendingIds,
+    /// Holds this request's room in the pending limit.
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PendingMessage {
+    async fn wait(mut self) -> Result<Response, ClientError> {
+        // The end ? unwraps the channel error, the inner result is directly returned.
+        (&mut self.response)
+            .await
+            .map_err(|_| ClientError::ConnectionClosed)?
+    }
+}
+
+impl Drop for PendingMessage {
+    fn drop(&mut self) {
+        // Closing first means the writer either sees the request as abandoned,
+        // or has already registered it and it gets removed here.
+        self.response.close();
+        self.in_flight_messages.lock().unwrap().remove(&self.id);
+        // Released last, so a request reusing the id cannot be removed above.
+        self.pending_ids.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Why a single websocket session stopped being served.
+enum SessionEnd {
+    /// The client was closed.
+    Shutdown,
+    /// Every client handle was dropped, nothing else can be sent.
+    ClientDropped,
+    /// The socket failed or was closed by the server.
+    ConnectionLost(ConnectionLoss),
+    /// The client started draining, and has to be done by the deadline.
+    Drain(Instant),
+    /// Every request in flight was answered, only returned by readers that were asked to.
+    Idle,
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn serve_connection(
-    outbound: mpsc::UnboundedReceiver<OutboundMessage>,
+    mut outbound: mpsc::UnboundedReceiver<OutboundMessage>,
     shutdown: Arc<SetOnce<()>>,
-    ws: WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>,
-    id_prefix: Option<String>,
+    drain: Arc<SetOnce<Instant>>,
+    mut ws: BoxTransport,
+    in_flight_messages: InFlightMessages,
+    rate_limiter: Arc<RateLimiter>,
+    events: broadcast::Sender<ConnectionEvent>,
+    out_of_band: broadcast::Sender<OutOfBandMessage>,
+    parse_mode: ParseMode,
+    reconnect: Option<(Box<dyn Connector>, ReconnectPolicy)>,
 ) {
-    let (write, read) = ws.split();
+    // Outlives the writers, which can be cancelled while a message waits for its budget.
+    let mut admitted = None;
+    loop {
+        let (mut write, mut read) = ws.split();
 
-    let in_flight_messages: Arc<Mutex<HashMap<String, Responder>>> = Default::default();
+        let end = tokio::select! {
+            end = ws_writer(
+                &mut outbound,
+                &mut admitted,
+                &mut write,
+                &in_flight_messages,
+                &rate_limiter,
+            ) => end,
+            end = ws_reader(
+                &mut read,
+                &in_flight_messages,
+                &events,
+                &out_of_band,
+                parse_mode,
+                false,
+            ) => end,
+            _ = shutdown.wait() => SessionEnd::Shutdown,
+            deadline = drain.wait() => SessionEnd::Drain(*deadline),
+        };
+        let end = match end {
+            SessionEnd::Drain(deadline) => {
+                // Later requests fail to be queued, the writer ends once the queued ones are sent.
+                outbound.close();
+                drain_session(
+                    deadline,
+                    ws_writer(
+                        &mut outbound,
+                        &mut admitted,
+                        &mut write,
+                        &in_flight_messages,
+                        &rate_limiter,
+                    ),
+                    &mut read,
+                    &in_flight_messages,
+                    &events,
+                    &out_of_band,
+                    parse_mode,
+                    &shutdown,
+                )
+                .await
+            }
+            end => end,
+        };
+
+        match end {
+            SessionEnd::ConnectionLost(reason) => {
+                warn!("connection to the server was lost: {}", reason);
+                fail_in_flight(&in_flight_messages, || {
+                    ClientError::ConnectionLost(reason.clone())
+                });
+                _ = events.send(ConnectionEvent::Disconnected(
+                    DisconnectReason::ConnectionLost(reason),
+                ));
+
+                let reconnected = match &reconnect {
+                    Some((connector, policy)) if !drain.initialized() => {
+                        if policy.outage == OutageBehavior::Reject
+                            && let Some(Admitted { outbound, .. }) = admitted.take()
+                        {
+                            _ = outbound.response.send(Err(ClientError::Disconnected));
+                        }
+                        reconnect_with_backoff(
+                            connector.as_ref(),
+                            policy,
+                            &mut outbound,
+                            &shutdown,
+                            &drain,
+                            &events,
+                        )
+                        .await
+                    }
+                    _ => None,
+                };
+                match reconnected {
+                    Some(reconnected) => {
+                        _ = events.send(ConnectionEvent::Connected);
+                        ws = reconnected;
+                    }
+                    None => break,
+                }
+            }
+            _ => {
+                match write.reunite(read).unwrap().close().await {
+                    Ok(_) => (),
+                    Err(tokio_tungstenite::tungstenite::Error::AlreadyClosed) => (),
+                    Err(e) => error!("failed to close connection: {}", e),
+                }
+                _ = events.send(ConnectionEvent::Disconnected(DisconnectReason::Closed));
+                break;
+            }
+        }
+    }
 
-    let writer = tokio::spawn(ws_writer(
-        outbound,
-        write,
-        in_flight_messages.clone(),
-        id_prefix,
-        shutdown.clone(),
+    // Nothing is sent or received anymore, so every request still waiting is abandoned.
+    _ = shutdown.set(());
+    outbound.close();
+    fail_in_flight(&in_flight_messages, || ClientError::ConnectionClosed);
+    if let Some(Admitted { outbound, .. }) = admitted {
+        _ = outbound.response.send(Err(ClientError::ConnectionClosed));
+    }
+    while let Ok(msg) = outbound.try_recv() {
+        // If discarded nothing got the message, which is fine.
+        _ = msg.response.send(Err(ClientError::ConnectionClosed));
+    }
+}
+
+/// Serves the session until every request already made is answered, without accepting new ones.
+/// Gives up at the deadline, or when the client is closed.
+#[allow(clippy::too_many_arguments)]
+async fn drain_session(
+    deadline: Instant,
+    writer: impl Future<Output = SessionEnd>,
+    read: &mut SplitStream<BoxTransport>,
+    in_flight_messages: &InFlightMessages,
+    events: &broadcast::Sender<ConnectionEvent>,
+    out_of_band: &broadcast::Sender<OutOfBandMessage>,
+    parse_mode: ParseMode,
+    shutdown: &SetOnce<()>,
+) -> SessionEnd {
+    // The writer is never restarted, it could be cancelled halfway through sending a message.
+    tokio::pin!(writer);
+    let mut writer_done = false;
+
+    loop {
+        if writer_done && in_flight_messages.lock().unwrap().is_empty() {
+            return SessionEnd::Shutdown;
+        }
+        tokio::select! {
+            end = &mut writer, if !writer_done => match end {
+                SessionEnd::ClientDropped => writer_done = true,
+                end => return end,
+            },
+            end = ws_reader(read, in_flight_messages, events, out_of_band, parse_mode, true) => match end {
+                SessionEnd::Idle => (),
+                end => return end,
+            },
+            _ = tokio::time::sleep_until(deadline) => {
+                warn!(
+                    "closing the connection with {} requests still unanswered after draining",
+                    in_flight_messages.lock().unwrap().len()
+                );
+                return SessionEnd::Shutdown;
+            }
+            _ = shutdown.wait() => return SessionEnd::Shutdown,
+        }
+    }
+}
+
+fn fail_in_flight(in_flight_messages: &InFlightMessages, error: impl Fn() -> ClientError) {
+    for (_, resp) in in_flight_messages.lock().unwrap().drain() {
+        // If discarded nothing got the message, which is fine.
+        _ = resp.send(Err(error()));
+    }
+}
+
+/// Attempts to reconnect until the policy gives up or the client shuts down.
+async fn reconnect_with_backoff(
+    connector: &dyn Connector,
+    policy: &ReconnectPolicy,
+    outbound: &mut mpsc::UnboundedReceiver<OutboundMessage>,
+    shutdown: &SetOnce<()>,
+    drain: &SetOnce<Instant>,
+    events: &broadcast::Sender<ConnectionEvent>,
+) -> Option<BoxTransport> {
+    let mut attempts = 0;
+    while !policy.is_exhausted(attempts) {
+        let delay = policy.backoff(attempts);
+        attempts += 1;
+        _ = events.send(ConnectionEvent::Reconnecting {
+            attempt: attempts,
+            delay,
+        });
+
+        during_outage(
+            tokio::time::sleep(delay),
+            outbound,
+            shutdown,
+            drain,
+            policy.outage,
+        )
+        .await?;
+        match during_outage(
+            connector.connect(),
+            outbound,
+            shutdown,
+            drain,
+            policy.outage,
+        )
+        .await?
+        {
+            Ok(ws) => {
+                info!("reconnected to the server after {} attempts", attempts);
+                return Some(ws);
+            }
+            Err(e) => warn!("reconnection attempt {} failed: {}", attempts, e),
+        }
+    }
+
+    error!("giving up on reconnecting after {} attempts", attempts);
+    _ = events.send(ConnectionEvent::Disconnected(
+        DisconnectReason::ReconnectFailed,
     ));
-    let reader = tokio::spawn(ws_reader(read, in_flight_messages, shutdown));
+    None
+}
 
-    if let (Ok(first), Ok(second)) = tokio::join!(writer, reader) {
-        match first.reunite(second).unwrap().close(None).await {
-            Ok(_) => (),
-            Err(tokio_tungstenite::tungstenite::Error::AlreadyClosed) => (),
-            Err(e) => error!("failed to close connection: {}", e),
+/// Runs a task while the connection is down, handling requests according to the outage behavior.
+/// Returns `None` if the client was shut down, drained or dropped while waiting.
+async fn during_outage<T>(
+    task: impl Future<Output = T>,
+    outbound: &mut mpsc::UnboundedReceiver<OutboundMessage>,
+    shutdown: &SetOnce<()>,
+    drain: &SetOnce<Instant>,
+    outage: OutageBehavior,
+) -> Option<T> {
+    tokio::pin!(task);
+    loop {
+        tokio::select! {
+            result = &mut task => return Some(result),
+            _ = shutdown.wait() => return None,
+            // Nothing is in flight while disconnected, so there is nothing to wait for.
+            _ = drain.wait() => return None,
+            msg = outbound.recv(), if outage == OutageBehavior::Reject => match msg {
+                Some(msg) => _ = msg.response.send(Err(ClientError::Disconnected)),
+                None => return None,
+            },
         }
     }
 }
 
+/// A message taken off the queue that is waiting for its rate limit budget.
+struct Admitted {
+    outbound: OutboundMessage,
+    /// When the message first had to wait, `None` if it has not waited yet.
+    waiting_since: Option<Instant>,
+}
+
+/// Writes queued messages until the client is dropped or the connection fails.
+/// Cancelling it while a message waits for its budget leaves the message in `admitted`,
+/// for the next writer to resume from.
 async fn ws_writer<
     WS: Sink<tokio_tungstenite::tungstenite::Message, Error = impl Debug> + Unpin,
 >(
-    mut to_send: mpsc::UnboundedReceiver<OutboundMessage>,
-    mut ws: WS,
-    in_flight_messages: Arc<Mutex<HashMap<String, Responder>>>,
-    id_prefix: Option<impl Into<String>>,
-    shutdown: Arc<SetOnce<()>>,
-) -> WS {
-    let mut id_gen = match id_prefix {
-        None => IdGenerator::default(),
-        Some(prefix) => IdGenerator::new_with_prefix(prefix),
-    };
-
-    while let Some(outbound) = tokio::select! {
-        msg = to_send.recv() => msg,
-        _ = shutdown.wait() => return ws,
-    } {
-        let id = id_gen.next();
+    to_send: &mut mpsc::UnboundedReceiver<OutboundMessage>,
+    admitted: &mut Option<Admitted>,
+    ws: &mut WS,
+    in_flight_messages: &InFlightMessages,
+    rate_limiter: &RateLimiter,
+) -> SessionEnd {
+    loop {
+        if admitted.is_none() {
+            let Some(outbound) = to_send.recv().await else {
+                return SessionEnd::ClientDropped;
+            };
+            *admitted = Some(Admitted {
+                outbound,
+                waiting_since: None,
+            });
+        }
+        let waiting = admitted.as_mut().expect("a message was admitted above");
+        if waiting.outbound.response.is_closed() {
+            // Abandoned requests do not use up the rate limits.
+            *admitted = None;
+            continue;
+        }
+        let kind = waiting.outbound.message.kind();
+        let ready_at = rate_limiter.ready_at(kind);
+        if ready_at > Instant::now() {
+            // The budget is only taken once the message is written,
+            // so a request abandoned while waiting leaves it to the next one.
+            waiting.waiting_since.get_or_insert_with(Instant::now);
+            tokio::select! {
+                _ = tokio::time::sleep_until(ready_at) => {}
+                _ = waiting.outbound.response.closed() => {}
+            }
+            continue;
+        }
+
+        let Admitted {
+            outbound,
+            waiting_since,
+        } = admitted.take().expect("a message was admitted above");
+        let waited = waiting_since.map_or(Duration::ZERO, |since| since.elapsed());
+        rate_limiter.take(kind, waited);
+        let id = outbound.id;
 
         let payload = MessageWrapper {
             message_id: id.clone(),
@@ -147,11 +782,14 @@ async fn ws_writer<
             }
         };
 
-        // TODO: Remove this response channel if the websocket fails to send.
-        in_flight_messages
-            .lock()
-            .await
-            .insert(id, outbound.response);
+        {
+            let mut in_flight_messages = in_flight_messages.lock().unwrap();
+            if outbound.response.is_closed() {
+                // The caller timed out or gave up before the message was sent.
+                continue;
+            }
+            in_flight_messages.insert(id.clone(), outbound.response);
+        }
 
         if let Err(e) = ws
             .send(tokio_tungstenite::tungstenite::Message::Text(
@@ -160,10 +798,12 @@ async fn ws_writer<
             .await
         {
             warn!("error sending message: {:?}", e);
+            return SessionEnd::ConnectionLost(ConnectionLoss::Failed(format!(
+                "failed to send a message: {:?}",
+                e
+            )));
         }
     }
-
-    ws
 }
 
 async fn ws_reader<
@@ -171,147 +811,1003 @@ async fn ws_reader<
             Item = tokio_tungstenite::tungstenite::Result<tokio_tungstenite::tungstenite::Message>,
         > + Unpin,
 >(
-    mut ws: WS,
-    in_flight_messages: Arc<Mutex<HashMap<String, Responder>>>,
-    shutdown: Arc<SetOnce<()>>,
-) -> WS {
-    while let Some(Ok(msg)) = tokio::select! {
-        msg = ws.next() => msg,
-        _ = shutdown.wait() => return ws,
-    } {
-        let text = msg.to_text().unwrap();
-        let (id, response) = match serde_json::from_str::<Response>(text) {
-            Ok(response) => (response.source_message_id.clone(), Ok(response)),
+    ws: &mut WS,
+    in_flight_messages: &InFlightMessages,
+    events: &broadcast::Sender<ConnectionEvent>,
+    out_of_band: &broadcast::Sender<OutOfBandMessage>,
+    parse_mode: ParseMode,
+    until_idle: bool,
+) -> SessionEnd {
+    loop {
+        let msg = match ws.next().await {
+            Some(Ok(msg)) => msg,
+            Some(Err(e)) => {
+                return SessionEnd::ConnectionLost(ConnectionLoss::Failed(e.to_string()));
+            }
+            None => return SessionEnd::ConnectionLost(ConnectionLoss::Ended),
+        };
+
+        let bytes = match &msg {
+            WsMessage::Text(text) => text.as_bytes(),
+            WsMessage::Binary(bytes) => bytes,
+            WsMessage::Close(frame) => {
+                // Tungstenite replies to the close frame itself, the stream ends after this.
+                return SessionEnd::ConnectionLost(ConnectionLoss::ClosedByServer {
+                    code: frame.as_ref().map(|frame| frame.code.into()),
+                    reason: frame
+                        .as_ref()
+                        .map(|frame| frame.reason.to_string())
+                        .unwrap_or_default(),
+                });
+            }
+            // Pings are answered by tungstenite, and raw frames are never produced when reading.
+            WsMessage::Ping(_) | WsMessage::Pong(_) | WsMessage::Frame(_) => continue,
+        };
+        let text = match std::str::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(e) => {
+                error!("Rejecting a binary message that is not valid UTF-8: {}", e);
+                _ = events.send(ConnectionEvent::ParseFailure {
+                    source_message_id: None,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let parsed = serde_json::from_str::<Response>(text).or_else(|e| match parse_mode {
+            ParseMode::Strict => Err(e),
+            // Only reparsed once strict parsing failed, as it is much slower.
+            ParseMode::Lenient => Response::from_str_lenient(text).map_err(|_| e),
+        });
+        let (id, response) = match parsed {
+            Ok(response) => {
+                if !response.diagnostics.is_empty() {
+                    warn!(
+                        "Replaced {} values that failed to parse in the response to {:?}",
+                        response.diagnostics.len(),
+                        response.source_message_id
+                    );
+                }
+                (response.source_message_id.clone(), Ok(response))
+            }
             Err(e) => match serde_json::from_str::<FallbackResponse>(text) {
-                Ok(response) => (
-                    response.source_message_id.clone(),
-                    Err(ClientError::MessageParsingError(e)),
-                ),
+                Ok(response) => {
+                    let e = ParseError::locate(text, e);
+                    _ = events.send(ConnectionEvent::ParseFailure {
+                        source_message_id: response.source_message_id.clone(),
+                        error: e.to_string(),
+                    });
+                    (
+                        response.source_message_id.clone(),
+                        Err(ClientError::MessageParsingError(e)),
+                    )
+                }
                 Err(_) => {
                     warn!(
                         "Message from server was not parsed successfully, and the fallback also failed. The RPC will never complete. {:?}",
                         e
                     );
+                    _ = events.send(ConnectionEvent::ParseFailure {
+                        source_message_id: None,
+                        error: e.to_string(),
+                    });
+                    send_out_of_band_json(out_of_band, text);
                     continue;
                 }
             },
         };
 
-        if let Some(id) = id {
-            if let Some(resp) = in_flight_messages.lock().await.remove(&id) {
-                // If discarded nothing got the message, which is fine.
-                _ = resp.send(response);
-            } else {
-                warn!("Unpaired outbound message: {:?}", response);
+        let resp = id
+            .as_ref()
+            .and_then(|id| in_flight_messages.lock().unwrap().remove(id));
+        if let Some(resp) = resp {
+            // If discarded nothing got the message, which is fine.
+            _ = resp.send(response);
+            if until_idle && in_flight_messages.lock().unwrap().is_empty() {
+                return SessionEnd::Idle;
+            }
+        } else {
+            warn!("Unpaired outbound message: {:?}", response);
+            _ = events.send(ConnectionEvent::UnpairedResponse {
+                source_message_id: id,
+            });
+            match response {
+                Ok(response) => {
+                    _ = out_of_band.send(OutOfBandMessage::Response(Arc::new(response)));
+                }
+                Err(_) => send_out_of_band_json(out_of_band, text),
             }
         }
     }
+}
 
-    ws
+/// Delivers a message that is not a response as JSON, if it is JSON at all.
+fn send_out_of_band_json(out_of_band: &broadcast::Sender<OutOfBandMessage>, text: &str) {
+    match serde_json::from_str(text) {
+        Ok(value) => _ = out_of_band.send(OutOfBandMessage::Json(Arc::new(value))),
+        Err(e) => warn!(
+            "Discarding a message from the server that is not JSON: {}",
+            e
+        ),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::responses::ResponseKind;
+    use crate::controller::backpressure::Backpressure;
+    use crate::controller::rate_limit::{RateLimit, RateLimits};
+    use crate::controller::remote_error::RemoteErrorKind;
+    use crate::controller::transport::MemoryTransport;
+    use crate::messages::MessageKind;
+    use crate::test_support::{Expectation, MockServer};
     use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
 
-    async fn serve_echo_requests(canned_responses: Vec<Response>) -> Vec<Message> {
-        let listener = TcpListener::bind("127.0.0.1:8080").await.unwrap();
-        let (stream, _) = listener.accept().await.unwrap();
-        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+    /// Accepts a single connection and reads everything without ever responding.
+    async fn serve_silently() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+        address
+    }
 
-        let mut messages = Vec::new();
-        for mut response in canned_responses {
-            let msg: MessageWrapper =
-                serde_json::from_str(ws.next().await.unwrap().unwrap().to_text().unwrap()).unwrap();
+    /// Drops the first connection after reading one message,
+    /// then answers every message on the connections after it.
+    async fn serve_flaky() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws.next().await;
+            drop(ws);
 
-            response.source_message_id = Some(msg.message_id.clone());
-            messages.push(msg);
-            ws.send(tokio_tungstenite::tungstenite::Message::Text(
-                serde_json::to_string(&response).unwrap().into(),
-            ))
-            .await
-            .unwrap();
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(msg)) = ws.next().await {
+                    let Ok(msg) = serde_json::from_str::<MessageWrapper>(msg.to_text().unwrap())
+                    else {
+                        continue;
+                    };
+                    let mut response = make_response(ResponseKind::Response);
+                    response.source_message_id = Some(msg.message_id);
+                    ws.send(tokio_tungstenite::tungstenite::Message::Text(
+                        serde_json::to_string(&response).unwrap().into(),
+                    ))
+                    .await
+                    .unwrap();
+                }
+            }
+        });
+        address
+    }
+
+    fn reconnecting(outage: OutageBehavior) -> ClientConfig {
+        ClientConfig {
+            reconnect: Some(ReconnectPolicy {
+                initial_backoff: Duration::from_millis(100),
+                jitter: 0.0,
+                outage,
+                ..Default::default()
+            }),
+            ..Default::default()
         }
+    }
 
-        messages.into_iter().map(|msg| msg.inner).collect()
+    fn get_root() -> Message {
+        Message::GetSlot {
+            slot_id: "Root".into(),
+            depth: 0,
+            include_component_data: false,
+        }
     }
 
     fn make_response(kind: ResponseKind) -> Response {
         Response {
             source_message_id: None,
-            success: false,
+            success: true,
             error_info: None,
+            diagnostics: Vec::new(),
             kind,
         }
     }
 
-    fn make_error_response(error_info: impl Into<String>) -> Response {
-        Response {
-            source_message_id: None,
-            success: true,
-            error_info: Some(error_info.into()),
-            kind: ResponseKind::Response,
+    #[tokio::test]
+    async fn ctor_success() {
+        let get_root_children = Message::GetSlot {
+            slot_id: "ROOT".into(),
+            depth: 1,
+            include_component_data: false,
+        };
+        let (client_end, mock) = MockServer::new()
+            .expect(Expectation::message(get_root_children.clone()))
+            .expect(Expectation::message(get_root_children.clone()).fail("Bad things!"))
+            .spawn();
+
+        let client = Client::with_transport(client_end, Default::default());
+        let mut response = client.send(get_root_children.clone()).await.unwrap();
+        let failure = client.send(get_root_children).await;
+
+        client.close().await.unwrap();
+        mock.verify().await.unwrap();
+
+        // Clear the source ID as it's random.
+        response.source_message_id = None;
+        assert_eq!(response, make_response(ResponseKind::Response));
+        let Err(ClientError::Remote(error)) = failure else {
+            panic!("expected a remote error, got {failure:?}");
+        };
+        assert!(error.source_message_id.is_some());
+        assert_eq!(error.request_kind, MessageKind::GetSlot);
+        assert_eq!(error.kind, RemoteErrorKind::Unknown);
+        assert_eq!(error.error_info.as_deref(), Some("Bad things!"));
+    }
+
+    #[tokio::test]
+    async fn pairs_out_of_order_replies() {
+        let remove = |slot_id: &str| Message::RemoveSlot {
+            slot_id: slot_id.into(),
+        };
+        let (client_end, mock) = MockServer::new()
+            .expect(
+                Expectation::message(remove("First"))
+                    .respond(ResponseKind::ComponentData { data: None })
+                    .after(Duration::from_millis(50)),
+            )
+            .expect(Expectation::message(remove("Second")))
+            .spawn();
+        let client = Client::with_transport(client_end, Default::default());
+
+        let first = client.send(remove("First"));
+        let second = async {
+            let response = client.send(remove("Second")).await;
+            // The first reply is still delayed.
+            assert_eq!(client.queue_depth().in_flight, 1);
+            response
+        };
+        let (first, second) = tokio::join!(first, second);
+
+        assert_eq!(
+            first.unwrap().kind,
+            ResponseKind::ComponentData { data: None }
+        );
+        assert_eq!(second.unwrap().kind, ResponseKind::Response);
+        client.close().await.unwrap();
+        mock.verify().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn times_out_when_reply_is_dropped() {
+        let (client_end, mock) = MockServer::new()
+            .expect(Expectation::message(get_root()).drop_reply())
+            .spawn();
+        let client = Client::with_transport(client_end, Default::default());
+
+        let result = client
+            .send_with_timeout(get_root(), Some(Duration::from_millis(50)))
+            .await;
+
+        assert!(matches!(result, Err(ClientError::Timeout(_))));
+        client.close().await.unwrap();
+        mock.verify().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn uses_the_configured_ids() {
+        let (client_end, mock) = MockServer::new()
+            .expect(Expectation::message(get_root()))
+            .spawn();
+        let config = ClientConfig {
+            message_ids: Arc::new(SequentialIds::new("Taco")),
+            ..Default::default()
+        };
+        let client = Client::with_transport(client_end, config);
+
+        let response = client.send(get_root()).await.unwrap();
+
+        assert_eq!(
+            response.source_message_id.as_deref(),
+            Some("RS_REPL_Taco_0")
+        );
+        client.close().await.unwrap();
+        mock.verify().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_duplicate_pending_ids() {
+        let (client_end, mock) = MockServer::new()
+            .expect(Expectation::message(get_root()).after(Duration::from_millis(50)))
+            .expect(Expectation::message(get_root()))
+            .spawn();
+        let client = Client::with_transport(client_end, Default::default());
+
+        let first = client.send_with_id("Taco", get_root());
+        let duplicate = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            client.send_with_id("Taco", get_root()).await
+        };
+        let (first, duplicate) = tokio::join!(first, duplicate);
+
+        assert_eq!(first.unwrap().source_message_id.as_deref(), Some("Taco"));
+        assert!(matches!(
+            duplicate,
+            Err(ClientError::DuplicateMessageId(id)) if id == "Taco"
+        ));
+        // The id can be reused once answered.
+        client.send_with_id("Taco", get_root()).await.unwrap();
+        client.close().await.unwrap();
+        mock.verify().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sends_with_id_and_timeout() {
+        let (client_end, mock) = MockServer::new()
+            .expect(Expectation::message(get_root()).drop_reply())
+            .expect(Expectation::message(get_root()))
+            .spawn();
+        let client = Client::with_transport(client_end, Default::default());
+
+        let timed_out = client
+            .send_with_id_and_timeout("Taco", get_root(), Some(Duration::from_millis(50)))
+            .await;
+        assert!(matches!(timed_out, Err(ClientError::Timeout(_))));
+
+        // The abandoned id can be used again.
+        let response = client
+            .send_with_id_and_timeout("Taco", get_root(), None)
+            .await
+            .unwrap();
+        assert_eq!(response.source_message_id.as_deref(), Some("Taco"));
+        client.close().await.unwrap();
+        mock.verify().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_times_out() {
+        let client = Client::connect(&serve_silently().await, None)
+            .await
+            .unwrap();
+
+        let result = client
+            .send_with_timeout(get_root(), Some(Duration::from_millis(50)))
+            .await;
+
+        assert!(matches!(result, Err(ClientError::Timeout(_))));
+        assert!(client.inner.in_flight_messages.lock().unwrap().is_empty());
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dropped_send_is_removed() {
+        let client = Client::connect(&serve_silently().await, None)
+            .await
+            .unwrap();
+
+        tokio::select! {
+            _ = client.send_with_timeout(get_root(), None) => panic!("the server never responds"),
+            _ = tokio::time::sleep(Duration::from_millis(50)) => (),
         }
+
+        assert!(client.inner.in_flight_messages.lock().unwrap().is_empty());
+        client.close().await.unwrap();
     }
 
     #[tokio::test]
-    async fn ctor_success() {
-        let requests = tokio::spawn(serve_echo_requests(vec![
-            make_response(ResponseKind::Response),
-            make_error_response("Bad things!"),
-        ]));
+    async fn reconnects_after_connection_lost() {
+        let client =
+            Client::connect_with_config(&serve_flaky().await, reconnecting(OutageBehavior::Hold))
+                .await
+                .unwrap();
 
-        tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+        let lost = client.send(get_root()).await;
+        assert!(matches!(lost, Err(ClientError::ConnectionLost(_))));
 
-        let client = Client::connect("ws://127.0.0.1:8080", None).await.unwrap();
-        let mut responses = vec![
-            client
-                .send(Message::GetSlot {
-                    slot_id: "ROOT".into(),
-                    depth: 1,
-                    include_component_data: false,
-                })
+        // Held until the connection is back.
+        let response = client.send(get_root()).await.unwrap();
+        assert_eq!(response.kind, ResponseKind::Response);
+        assert!(!client.is_closed());
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_during_outage() {
+        let client =
+            Client::connect_with_config(&serve_flaky().await, reconnecting(OutageBehavior::Reject))
                 .await
-                .unwrap(),
-            client
-                .send(Message::GetSlot {
-                    slot_id: "ROOT".into(),
-                    depth: 1,
-                    include_component_data: false,
-                })
+                .unwrap();
+
+        let lost = client.send(get_root()).await;
+        assert!(matches!(lost, Err(ClientError::ConnectionLost(_))));
+
+        let rejected = client.send(get_root()).await;
+        assert!(matches!(rejected, Err(ClientError::Disconnected)));
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn closes_when_connection_lost() {
+        let client = Client::connect(&serve_flaky().await, None).await.unwrap();
+
+        let lost = client.send(get_root()).await;
+        assert!(matches!(lost, Err(ClientError::ConnectionLost(_))));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(client.is_closed());
+        let closed = client.send(get_root()).await;
+        assert!(matches!(closed, Err(ClientError::ConnectionClosed)));
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reports_reconnection_events() {
+        let client =
+            Client::connect_with_config(&serve_flaky().await, reconnecting(OutageBehavior::Hold))
                 .await
-                .unwrap(),
-        ];
+                .unwrap();
+        let mut events = client.subscribe_events();
+
+        assert!(client.send(get_root()).await.is_err());
+        client.send(get_root()).await.unwrap();
+
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            ConnectionEvent::Disconnected(DisconnectReason::ConnectionLost(_))
+        ));
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            ConnectionEvent::Reconnecting { attempt: 1, .. }
+        ));
+        assert_eq!(events.recv().await.unwrap(), ConnectionEvent::Connected);
 
-        client.close().await;
+        client.close().await.unwrap();
         assert_eq!(
-            requests.await.unwrap(),
-            vec![
-                Message::GetSlot {
-                    slot_id: "ROOT".into(),
-                    depth: 1,
-                    include_component_data: false,
+            events.recv().await.unwrap(),
+            ConnectionEvent::Disconnected(DisconnectReason::Closed)
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_unpaired_responses() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            // Wait for the client to subscribe before pushing anything.
+            ws.next().await;
+            for text in [
+                serde_json::to_string(&make_response(ResponseKind::Response)).unwrap(),
+                "{ \"sourceMessageId\": \"Nope\" }".to_owned(),
+            ] {
+                ws.send(tokio_tungstenite::tungstenite::Message::Text(text.into()))
+                    .await
+                    .unwrap();
+            }
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let client = Client::connect(&address, None).await.unwrap();
+        let mut events = client.subscribe_events();
+        _ = client
+            .send_with_timeout(get_root(), Some(Duration::from_millis(1)))
+            .await;
+
+        assert_eq!(
+            events.recv().await.unwrap(),
+            ConnectionEvent::UnpairedResponse {
+                source_message_id: None
+            }
+        );
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            ConnectionEvent::ParseFailure {
+                source_message_id: None,
+                ..
+            }
+        ));
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn connects_with_a_closure() {
+        let client = Client::connect_with(
+            || async {
+                let (client_end, _) = MockServer::new()
+                    .expect(Expectation::message(get_root()))
+                    .spawn();
+                Ok::<_, ClientError>(client_end)
+            },
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        assert!(client.send(get_root()).await.is_ok());
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn fails_fast_when_queue_is_full() {
+        let client = Client::connect_with_config(
+            &serve_silently().await,
+            ClientConfig {
+                max_pending_requests: Some(1),
+                backpressure: Backpressure::FailFast,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        tokio::select! {
+            _ = client.send(get_root()) => panic!("the server never responds"),
+            _ = async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                assert_eq!(client.queue_depth(), QueueDepth { queued: 0, in_flight: 1 });
+                let full = client.send(get_root()).await;
+                assert!(matches!(full, Err(ClientError::QueueFull)));
+            } => (),
+        }
+
+        assert_eq!(client.queue_depth(), QueueDepth::default());
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn waits_when_queue_is_full() {
+        let client = Client::connect_with_config(
+            &serve_silently().await,
+            ClientConfig {
+                max_pending_requests: Some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let first = client.send_with_timeout(get_root(), Some(Duration::from_millis(100)));
+        let second = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            client
+                .send_with_timeout(get_root(), Some(Duration::from_millis(50)))
+                .await
+        };
+        let (first, second) = tokio::join!(first, second);
+
+        // The second request spends its whole timeout waiting for room.
+        assert!(matches!(first, Err(ClientError::Timeout(_))));
+        assert!(matches!(second, Err(ClientError::Timeout(_))));
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn typed_request_rejects_mismatched_kind() {
+        let (client_end, _) = MockServer::new()
+            .expect(
+                Expectation::message(get_root())
+                    .respond(ResponseKind::ComponentData { data: None }),
+            )
+            .spawn();
+        let client = Client::with_transport(client_end, Default::default());
+
+        let result = client.get_slot("Root", 0, false).await;
+
+        assert!(matches!(
+            result,
+            Err(ClientError::UnexpectedResponse { received, .. })
+                if *received == ResponseKind::ComponentData { data: None }
+        ));
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_binary_frames_and_skips_pings() {
+        let (client_end, mut server_end) = MemoryTransport::pair();
+        tokio::spawn(async move {
+            let msg = server_end.next().await.unwrap().unwrap();
+            let msg: MessageWrapper = serde_json::from_str(msg.to_text().unwrap()).unwrap();
+            let mut response = make_response(ResponseKind::Response);
+            response.source_message_id = Some(msg.message_id);
+            for frame in [
+                WsMessage::Ping("Taco".into()),
+                WsMessage::Binary(vec![0xff, 0xfe].into()),
+                WsMessage::Binary(serde_json::to_vec(&response).unwrap().into()),
+            ] {
+                server_end.send(frame).await.unwrap();
+            }
+            while let Some(Ok(_)) = server_end.next().await {}
+        });
+        let client = Client::with_transport(client_end, Default::default());
+        let mut events = client.subscribe_events();
+
+        let response = client.send(get_root()).await.unwrap();
+
+        assert_eq!(response.kind, ResponseKind::Response);
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            ConnectionEvent::ParseFailure {
+                source_message_id: None,
+                ..
+            }
+        ));
+        client.close().await.unwrap();
+    }
+
+    /// Answers every message with a component that has a broken member.
+    fn serve_broken_component(mut server_end: MemoryTransport) {
+        tokio::spawn(async move {
+            while let Some(Ok(msg)) = server_end.next().await {
+                let msg: MessageWrapper = serde_json::from_str(msg.to_text().unwrap()).unwrap();
+                let response = serde_json::json!({
+                    "$type": "componentData",
+                    "data": {
+                        "id": "Taco",
+                        "isReferenceOnly": false,
+                        "componentType": "FrooxEngine.Taco",
+                        "members": {
+                            "Shell": { "$type": "bool", "id": "Shell", "value": "Crunchy" },
+                        },
+                    },
+                    "sourceMessageId": msg.message_id,
+                    "success": true,
+                    "errorInfo": null,
+                });
+                let text = WsMessage::Text(response.to_string().into());
+                server_end.send(text).await.unwrap();
+            }
+        });
+    }
+
+    #[tokio::test]
+    async fn lenient_mode_reports_broken_members() {
+        let (client_end, server_end) = MemoryTransport::pair();
+        serve_broken_component(server_end);
+        let client = Client::with_transport(
+            client_end,
+            ClientConfig {
+                parse_mode: ParseMode::Lenient,
+                ..Default::default()
+            },
+        );
+
+        let (component, diagnostics) = client
+            .request_with_diagnostics(
+                crate::requests::GetComponent {
+                    component_id: "Taco".into(),
                 },
-                Message::GetSlot {
-                    slot_id: "ROOT".into(),
-                    depth: 1,
-                    include_component_data: false,
-                }
-            ]
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(component.id, "Taco");
+        assert!(matches!(
+            component.members["Shell"],
+            crate::data_model::Member::Unknown { .. }
+        ));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, "data.members.Shell");
+        assert_eq!(diagnostics[0].component_id.as_deref(), Some("Taco"));
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn strict_mode_fails_broken_members() {
+        let (client_end, server_end) = MemoryTransport::pair();
+        serve_broken_component(server_end);
+        let client = Client::with_transport(client_end, Default::default());
+
+        let result = client.get_component("Taco").await;
+
+        let Err(ClientError::MessageParsingError(e)) = result else {
+            panic!("expected a parsing error, got {:?}", result);
+        };
+        assert_eq!(e.path.as_deref(), Some("data.members.Shell"));
+        assert_eq!(e.type_name.as_deref(), Some("bool"));
+        assert_eq!(e.fragment.unwrap()["value"], "Crunchy");
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn fails_pending_with_close_code() {
+        let (client_end, mut server_end) = MemoryTransport::pair();
+        tokio::spawn(async move {
+            server_end.next().await;
+            server_end
+                .send(WsMessage::Close(Some(CloseFrame {
+                    code: CloseCode::Away,
+                    reason: "Restarting".into(),
+                })))
+                .await
+                .unwrap();
+        });
+        let client = Client::with_transport(client_end, Default::default());
+
+        let result = client.send(get_root()).await;
+
+        let Err(ClientError::ConnectionLost(loss)) = result else {
+            panic!("expected the connection to be lost, got {result:?}");
+        };
+        assert_eq!(
+            loss,
+            ConnectionLoss::ClosedByServer {
+                code: Some(1001),
+                reason: "Restarting".into(),
+            }
         );
+        assert_eq!(loss.to_string(), "closed by the server (1001): Restarting");
+        client.close().await.unwrap();
+    }
 
-        for response in responses.iter_mut() {
-            // Clear the source ID as they're random.
-            response.source_message_id = None;
+    #[tokio::test]
+    async fn delivers_out_of_band_messages() {
+        let (client_end, mut server_end) = MemoryTransport::pair();
+        let client = Client::with_transport(client_end, Default::default());
+        let mut out_of_band = client.subscribe_out_of_band();
+        for text in [
+            serde_json::to_string(&make_response(ResponseKind::Response)).unwrap(),
+            "{ \"$type\": \"notification\", \"value\": 1 }".to_owned(),
+            "Not JSON".to_owned(),
+        ] {
+            server_end.send(WsMessage::Text(text.into())).await.unwrap();
         }
+
         assert_eq!(
-            responses,
-            vec![
-                make_response(ResponseKind::Response),
-                make_error_response("Bad things!"),
-            ]
+            out_of_band.recv().await.unwrap(),
+            OutOfBandMessage::Response(Arc::new(make_response(ResponseKind::Response)))
         );
+        assert_eq!(
+            out_of_band.recv().await.unwrap(),
+            OutOfBandMessage::Json(Arc::new(serde_json::json!({
+                "$type": "notification",
+                "value": 1,
+            })))
+        );
+        client.close().await.unwrap();
+        assert!(matches!(
+            out_of_band.recv().await,
+            Err(broadcast::error::RecvError::Closed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn clones_share_the_connection() {
+        let (client_end, mock) = MockServer::new()
+            .expect(Expectation::message(get_root()))
+            .expect(Expectation::message(get_root()))
+            .spawn();
+        let client = Client::with_transport(client_end, Default::default());
+        let mut events = client.subscribe_events();
+
+        let other = client.clone();
+        tokio::spawn(async move { other.send(get_root()).await })
+            .await
+            .unwrap()
+            .unwrap();
+        client.send(get_root()).await.unwrap();
+
+        // Dropping the last handle closes the connection.
+        drop(client);
+        assert_eq!(
+            events.recv().await.unwrap(),
+            ConnectionEvent::Disconnected(DisconnectReason::Closed)
+        );
+        mock.verify().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn shutdown_handle_closes_every_handle() {
+        let (client_end, _) = MockServer::new().spawn();
+        let client = Client::with_transport(client_end, Default::default());
+        let other = client.clone();
+
+        let shutdown = client.shutdown_handle();
+        shutdown.shutdown();
+
+        assert!(shutdown.is_shutdown());
+        assert!(other.is_closed());
+        other.close().await.unwrap();
+        let closed = client.send(get_root()).await;
+        assert!(matches!(closed, Err(ClientError::ConnectionClosed)));
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn drain_waits_for_replies() {
+        let (client_end, mock) = MockServer::new()
+            .expect(Expectation::message(get_root()).after(Duration::from_millis(50)))
+            .spawn();
+        let client = Client::with_transport(client_end, Default::default());
+        let other = client.clone();
+        let pending = tokio::spawn(async move { other.send(get_root()).await });
+        // Let the request be queued before draining.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let other = client.clone();
+        let drained = tokio::spawn(client.drain(Duration::from_secs(5)));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(other.is_closed());
+        drop(other);
+
+        drained.await.unwrap().unwrap();
+        assert_eq!(pending.await.unwrap().unwrap().kind, ResponseKind::Response);
+        mock.verify().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn drain_sends_requests_waiting_for_their_budget() {
+        let (client_end, mock) = MockServer::new()
+            .expect(Expectation::message(get_root()))
+            .expect(Expectation::message(get_root()))
+            .spawn();
+        let config = ClientConfig {
+            rate_limits: RateLimits {
+                overall: Some(RateLimit::new(10.0, 1)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = Client::with_transport(client_end, config);
+        let pending: Vec<_> = (0..2)
+            .map(|_| {
+                let other = client.clone();
+                tokio::spawn(async move { other.send(get_root()).await })
+            })
+            .collect();
+        // The second request is held back by the rate limit when the drain starts.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        client.drain(Duration::from_secs(5)).await.unwrap();
+
+        for pending in pending {
+            assert_eq!(pending.await.unwrap().unwrap().kind, ResponseKind::Response);
+        }
+        mock.verify().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn drain_rejects_requests_over_the_limit() {
+        for backpressure in [Backpressure::Wait, Backpressure::FailFast] {
+            let (client_end, _) = MockServer::new()
+                .expect(Expectation::message(get_root()).drop_reply())
+                .spawn();
+            let config = ClientConfig {
+                max_pending_requests: Some(1),
+                backpressure,
+                ..Default::default()
+            };
+            let client = Client::with_transport(client_end, config);
+            let other = client.clone();
+            let _pending =
+                tokio::spawn(async move { other.send_with_timeout(get_root(), None).await });
+            tokio::time::sleep(Duration::from_millis(10)).await;
+
+            client.shutdown_handle().drain(Duration::from_secs(5));
+            let rejected =
+                tokio::time::timeout(Duration::from_secs(1), client.send(get_root())).await;
+
+            assert!(matches!(rejected, Ok(Err(ClientError::ConnectionClosed))));
+            client.close().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn abandoned_requests_leave_their_budget() {
+        let (client_end, mock) = MockServer::new()
+            .expect(Expectation::message(get_root()))
+            .expect(Expectation::message(get_root()))
+            .spawn();
+        let config = ClientConfig {
+            rate_limits: RateLimits {
+                overall: Some(RateLimit::new(2.0, 1)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = Client::with_transport(client_end, config);
+        let start = Instant::now();
+
+        client.send(get_root()).await.unwrap();
+        let abandoned = client
+            .send_with_timeout(get_root(), Some(Duration::from_millis(50)))
+            .await;
+        assert!(matches!(abandoned, Err(ClientError::Timeout(_))));
+        client.send(get_root()).await.unwrap();
+
+        // The abandoned request would have pushed this one back to a second.
+        assert!(start.elapsed() < Duration::from_millis(900));
+        let stats = client.rate_limit_stats();
+        assert_eq!(stats.messages, 2);
+        assert_eq!(stats.delayed, 1);
+        client.close().await.unwrap();
+        mock.verify().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn drain_fails_unanswered_requests() {
+        let (client_end, mock) = MockServer::new()
+            .expect(Expectation::message(get_root()).drop_reply())
+            .spawn();
+        let client = Client::with_transport(client_end, Default::default());
+        let other = client.clone();
+        let pending = tokio::spawn(async move { other.send_with_timeout(get_root(), None).await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        client.shutdown_handle().drain(Duration::from_millis(50));
+        let rejected = client.send(get_root()).await;
+        client.drain(Duration::from_secs(5)).await.unwrap();
+
+        assert!(matches!(rejected, Err(ClientError::ConnectionClosed)));
+        assert!(matches!(
+            pending.await.unwrap(),
+            Err(ClientError::ConnectionClosed)
+        ));
+        mock.verify().await.unwrap();
+    }
+
+    /// A transport that panics the task reading from it.
+    struct PanickingTransport;
+
+    impl Stream for PanickingTransport {
+        type Item = tokio_tungstenite::tungstenite::Result<WsMessage>;
+
+        fn poll_next(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Option<Self::Item>> {
+            panic!("the transport exploded");
+        }
+    }
+
+    impl Sink<WsMessage> for PanickingTransport {
+        type Error = tokio_tungstenite::tungstenite::Error;
+
+        fn poll_ready(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Result<(), Self::Error>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: std::pin::Pin<&mut Self>, _item: WsMessage) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Result<(), Self::Error>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Result<(), Self::Error>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn close_reports_task_panics() {
+        let client = Client::with_transport(PanickingTransport, Default::default());
+        let other = client.clone();
+        // Let the task start reading before it is asked to shut down.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let result = client.close().await;
+
+        assert!(matches!(result, Err(ClientError::TaskFailed(_))));
+        // Every handle sees the same failure.
+        assert!(matches!(
+            other.close().await,
+            Err(ClientError::TaskFailed(_))
+        ));
     }
 }
@@ -0,0 +1,55 @@
+use crate::controller::backpressure::Backpressure;
+use crate::controller::id_generator::{IdStrategy, SequentialIds};
+use crate::controller::rate_limit::RateLimits;
+use crate::controller::reconnect::ReconnectPolicy;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long a request waits for its response before failing with [`ClientError::Timeout`](crate::ClientError::Timeout).
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Settings used when connecting a [`Client`](crate::Client).
+#[derive(Clone, Debug)]
+pub struct ClientConfig {
+    /// Generates the ids of requests sent without one, [`SequentialIds`] with a random prefix by default.
+    pub message_ids: Arc<dyn IdStrategy>,
+    /// Timeout used by [`Client::send`](crate::Client::send), `None` waits forever.
+    pub default_timeout: Option<Duration>,
+    /// Reconnects dropped connections when set, otherwise the client closes.
+    pub reconnect: Option<ReconnectPolicy>,
+    /// How many requests may be queued or in flight at once, `None` is unbounded.
+    /// Limits of 0 are raised to 1, and limits over [`Semaphore::MAX_PERMITS`](tokio::sync::Semaphore::MAX_PERMITS)
+    /// are lowered to it.
+    pub max_pending_requests: Option<usize>,
+    /// What happens to requests over the pending limit.
+    pub backpressure: Backpressure,
+    /// How fast messages may be written to the connection, unlimited by default.
+    pub rate_limits: RateLimits,
+    /// What happens to responses that fail to parse.
+    pub parse_mode: ParseMode,
+}
+
+/// How responses that fail to parse are handled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// The request fails with [`ClientError::MessageParsingError`](crate::ClientError::MessageParsingError).
+    #[default]
+    Strict,
+    /// Broken slots, components and members are replaced by placeholders, see
+    /// [`Response::from_str_lenient`](crate::Response::from_str_lenient).
+    Lenient,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            message_ids: Arc::new(SequentialIds::default()),
+            default_timeout: Some(DEFAULT_TIMEOUT),
+            reconnect: None,
+            max_pending_requests: None,
+            backpressure: Backpressure::default(),
+            rate_limits: RateLimits::default(),
+            parse_mode: ParseMode::default(),
+        }
+    }
+}
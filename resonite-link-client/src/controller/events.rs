@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+/// Something that happened to the connection of a [`Client`](crate::Client),
+/// see [`Client::subscribe_events`](crate::Client::subscribe_events).
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConnectionEvent {
+    /// The connection was re-established after being lost.
+    ///
+    /// It is not sent for the first connection, which is implicit:
+    /// a client only exists once it is connected, before anything can subscribe.
+    Connected,
+    /// The connection ended.
+    Disconnected(DisconnectReason),
+    /// A reconnection attempt will be made after the delay.
+    Reconnecting { attempt: u32, delay: Duration },
+    /// A response arrived that does not belong to any in flight request.
+    UnpairedResponse { source_message_id: Option<String> },
+    /// A message from the server could not be parsed.
+    ParseFailure {
+        source_message_id: Option<String>,
+        error: String,
+    },
+}
+
+/// Why the connection ended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DisconnectReason {
+    /// The client was closed, or every handle to it was dropped.
+    Closed,
+    /// The socket failed or was closed by the server.
+    ConnectionLost(ConnectionLoss),
+    /// Every reconnection attempt allowed by the policy failed.
+    ReconnectFailed,
+}
+
+impl std::fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisconnectReason::Closed => f.write_str("the client was closed"),
+            DisconnectReason::ConnectionLost(reason) => {
+                write!(f, "the connection was lost: {}", reason)
+            }
+            DisconnectReason::ReconnectFailed => f.write_str("reconnecting to the server failed"),
+        }
+    }
+}
+
+/// How the connection to the server was lost.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionLoss {
+    /// The server sent a close frame, the code is missing if the frame had no payload.
+    ClosedByServer { code: Option<u16>, reason: String },
+    /// The stream ended without a close frame.
+    Ended,
+    /// Reading from or writing to the socket failed.
+    Failed(String),
+}
+
+impl std::fmt::Display for ConnectionLoss {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionLoss::ClosedByServer {
+                code: Some(code),
+                reason,
+            } if !reason.is_empty() => write!(f, "closed by the server ({}): {}", code, reason),
+            ConnectionLoss::ClosedByServer {
+                code: Some(code), ..
+            } => write!(f, "closed by the server ({})", code),
+            ConnectionLoss::ClosedByServer { code: None, .. } => {
+                f.write_str("closed by the server")
+            }
+            ConnectionLoss::Ended => f.write_str("the stream ended"),
+            ConnectionLoss::Failed(error) => write!(f, "the socket failed: {}", error),
+        }
+    }
+}
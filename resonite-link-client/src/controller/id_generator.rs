@@ -1,34 +1,68 @@
 use rand::random;
+use std::fmt::Debug;
 use std::sync::atomic::{AtomicU64, Ordering};
 
-pub struct IdGenerator {
+/// Chooses the `messageId` of requests that are not given one by the caller,
+/// see [`ClientConfig::message_ids`](crate::ClientConfig::message_ids).
+///
+/// Ids only need to be unique among the requests still waiting for a response,
+/// a request whose id is already pending fails with
+/// [`ClientError::DuplicateMessageId`](crate::ClientError::DuplicateMessageId).
+pub trait IdStrategy: Debug + Send + Sync {
+    fn next_id(&self) -> String;
+}
+
+/// Numbers requests in order as `RS_REPL_<prefix>_<n>`.
+/// With a fixed prefix the ids are the same on every run, which suits snapshot tests.
+#[derive(Debug)]
+pub struct SequentialIds {
     prefix: String,
     id: AtomicU64,
 }
 
-impl Default for IdGenerator {
+impl Default for SequentialIds {
+    /// Uses a random prefix, so separate clients are unlikely to share ids.
     fn default() -> Self {
-        Self {
-            prefix: format!("{:X}", random::<u32>()),
-            id: AtomicU64::new(0),
-        }
+        Self::new(format!("{:X}", random::<u32>()))
     }
 }
 
-impl IdGenerator {
-    pub fn new_with_prefix(prefix: impl Into<String>) -> Self {
+impl SequentialIds {
+    pub fn new(prefix: impl Into<String>) -> Self {
         Self {
             prefix: prefix.into(),
             id: AtomicU64::new(0),
         }
     }
+}
 
-    pub fn next(&mut self) -> String {
+impl IdStrategy for SequentialIds {
+    fn next_id(&self) -> String {
         let id = self.id.fetch_add(1, Ordering::Relaxed);
         format!("RS_REPL_{}_{}", self.prefix, id)
     }
 }
 
+/// Random version 4 UUIDs, for several processes sharing a connection or its logs.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct UuidIds;
+
+impl IdStrategy for UuidIds {
+    fn next_id(&self) -> String {
+        let bytes = random::<u128>();
+        // Set the version to 4 and the variant to RFC 4122.
+        let bytes = (bytes & !(0xF << 76) & !(0x3 << 62)) | (0x4 << 76) | (0x2 << 62);
+        let hex = format!("{:032x}", bytes);
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -36,7 +70,23 @@ mod tests {
 
     #[test]
     fn generates_two_unique() {
-        let mut id_gen = IdGenerator::default();
-        assert_ne!(id_gen.next(), id_gen.next());
+        let id_gen = SequentialIds::default();
+        assert_ne!(id_gen.next_id(), id_gen.next_id());
+    }
+
+    #[test]
+    fn sequential_ids_are_deterministic() {
+        let ids = SequentialIds::new("Taco");
+        assert_eq!(ids.next_id(), "RS_REPL_Taco_0");
+        assert_eq!(ids.next_id(), "RS_REPL_Taco_1");
+    }
+
+    #[test]
+    fn generates_uuids() {
+        let id = UuidIds.next_id();
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(id, UuidIds.next_id());
     }
 }
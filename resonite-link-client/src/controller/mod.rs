@@ -1,4 +1,26 @@
 mod id_generator;
+mod out_of_band;
+mod rate_limit;
+mod backpressure;
+mod builder;
 mod command_client;
+mod config;
+mod events;
+mod reconnect;
+mod remote_error;
+mod session_manager;
+mod transport;
+mod typed_client;
 
-pub use command_client::Client;
+pub use backpressure::{Backpressure, QueueDepth};
+pub use builder::ClientBuilder;
+pub use command_client::{Client, ClientError, ShutdownHandle};
+pub use config::{ClientConfig, DEFAULT_TIMEOUT, ParseMode};
+pub use events::{ConnectionEvent, ConnectionLoss, DisconnectReason};
+pub use id_generator::{IdStrategy, SequentialIds, UuidIds};
+pub use out_of_band::OutOfBandMessage;
+pub use rate_limit::{RateLimit, RateLimitStats, RateLimits};
+pub use reconnect::{OutageBehavior, ReconnectPolicy};
+pub use remote_error::{RemoteError, RemoteErrorKind};
+pub use session_manager::{SessionError, SessionHealth, SessionManager};
+pub use transport::{BoxTransport, Connector, MemoryTransport, Transport, WebSocketConnector};
@@ -0,0 +1,14 @@
+use crate::responses::Response;
+use std::sync::Arc;
+
+/// A message from the server that does not answer any in flight request,
+/// see [`Client::subscribe_out_of_band`](crate::Client::subscribe_out_of_band).
+///
+/// The contents are shared between every subscriber.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutOfBandMessage {
+    /// A response without a `sourceMessageId`, or with one that is not in flight.
+    Response(Arc<Response>),
+    /// JSON that could not be parsed as a response, such as a message type this client does not know.
+    Json(Arc<serde_json::Value>),
+}
@@ -0,0 +1,276 @@
+use crate::messages::MessageKind;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// A token bucket, allowing bursts of up to `burst` messages and refilling at `per_second`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// Messages allowed per second once the burst is used up, rates below one message
+    /// every thousand seconds are raised to it.
+    pub per_second: f64,
+    /// Messages that can be sent at once after being idle, at least one.
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub fn new(per_second: f64, burst: u32) -> Self {
+        Self { per_second, burst }
+    }
+
+    fn rate(&self) -> f64 {
+        // Also replaces NaN.
+        self.per_second.max(0.001)
+    }
+
+    fn capacity(&self) -> f64 {
+        self.burst.max(1) as f64
+    }
+}
+
+/// Limits how fast messages are written to the connection, see [`RateLimit`].
+///
+/// A message has to fit both the overall budget and the budget for its kind,
+/// see [`MessageKind::is_mutation`]. Messages are written in the order they were sent,
+/// so a message waiting for its budget also holds back the messages after it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RateLimits {
+    /// Shared by every message.
+    pub overall: Option<RateLimit>,
+    /// Only for messages that read the world.
+    pub reads: Option<RateLimit>,
+    /// Only for messages that change the world.
+    pub mutations: Option<RateLimit>,
+}
+
+/// How long messages were held back by the [`RateLimits`], see
+/// [`Client::rate_limit_stats`](crate::Client::rate_limit_stats).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RateLimitStats {
+    /// Messages that passed through the limiter.
+    pub messages: u64,
+    /// Messages that had to wait for their budget.
+    pub delayed: u64,
+    /// The sum of every wait.
+    pub total_wait: Duration,
+    /// The longest single wait.
+    pub longest_wait: Duration,
+}
+
+impl RateLimitStats {
+    /// The average wait across every message, including the ones that did not wait.
+    pub fn average_wait(&self) -> Duration {
+        match u32::try_from(self.messages) {
+            Ok(0) => Duration::ZERO,
+            Ok(messages) => self.total_wait / messages,
+            Err(_) => self.total_wait.div_f64(self.messages as f64),
+        }
+    }
+}
+
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: limit.capacity(),
+            updated: now,
+        }
+    }
+
+    /// How long until a token is available, without taking it.
+    fn wait(&self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        let tokens = (self.tokens + elapsed * self.limit.rate()).min(self.limit.capacity());
+        if tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - tokens) / self.limit.rate())
+        }
+    }
+
+    /// Takes a token, going into debt if there is none, and returns how long until it is paid off.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.rate()).min(self.limit.capacity());
+        self.updated = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.limit.rate())
+        }
+    }
+}
+
+struct Buckets {
+    overall: Option<Bucket>,
+    reads: Option<Bucket>,
+    mutations: Option<Bucket>,
+}
+
+/// The state of [`RateLimits`], shared by the client and the task writing to the connection.
+pub(crate) struct RateLimiter {
+    buckets: Mutex<Buckets>,
+    stats: Mutex<RateLimitStats>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        let now = Instant::now();
+        let bucket = |limit: Option<RateLimit>| limit.map(|limit| Bucket::new(limit, now));
+        Self {
+            buckets: Mutex::new(Buckets {
+                overall: bucket(limits.overall),
+                reads: bucket(limits.reads),
+                mutations: bucket(limits.mutations),
+            }),
+            stats: Default::default(),
+        }
+    }
+
+    /// Takes the budget for a message, and how long to wait before it may be written.
+    fn reserve(&self, kind: MessageKind, now: Instant) -> Duration {
+        let mut buckets = self.buckets.lock().unwrap();
+        let Buckets {
+            overall,
+            reads,
+            mutations,
+        } = &mut *buckets;
+        let by_kind = if kind.is_mutation() { mutations } else { reads };
+        [overall, by_kind]
+            .into_iter()
+            .flatten()
+            .map(|bucket| bucket.reserve(now))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// When the budget for a message will be available, without taking it.
+    pub fn ready_at(&self, kind: MessageKind) -> Instant {
+        let now = Instant::now();
+        let buckets = self.buckets.lock().unwrap();
+        let by_kind = if kind.is_mutation() {
+            &buckets.mutations
+        } else {
+            &buckets.reads
+        };
+        let wait = [&buckets.overall, by_kind]
+            .into_iter()
+            .flatten()
+            .map(|bucket| bucket.wait(now))
+            .max()
+            .unwrap_or_default();
+        now + wait
+    }
+
+    /// Takes the budget for a message that is being written, once it is [ready](Self::ready_at).
+    /// `waited` is how long the message was held back for it.
+    pub fn take(&self, kind: MessageKind, waited: Duration) {
+        self.reserve(kind, Instant::now());
+        let mut stats = self.stats.lock().unwrap();
+        stats.messages += 1;
+        if !waited.is_zero() {
+            stats.delayed += 1;
+            stats.total_wait += waited;
+            stats.longest_wait = stats.longest_wait.max(waited);
+        }
+    }
+
+    pub fn stats(&self) -> RateLimitStats {
+        *self.stats.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_bursts_then_refills() {
+        let start = Instant::now();
+        let mut bucket = Bucket::new(RateLimit::new(10.0, 2), start);
+
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::from_millis(100));
+        // The debt is paid off before the next token is earned.
+        assert_eq!(bucket.reserve(start), Duration::from_millis(200));
+
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn budgets_are_split_by_kind() {
+        let limiter = RateLimiter::new(RateLimits {
+            mutations: Some(RateLimit::new(1.0, 1)),
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        assert_eq!(
+            limiter.reserve(MessageKind::UpdateSlot, now),
+            Duration::ZERO
+        );
+        assert_eq!(
+            limiter.reserve(MessageKind::UpdateSlot, now),
+            Duration::from_secs(1)
+        );
+        assert_eq!(limiter.reserve(MessageKind::GetSlot, now), Duration::ZERO);
+    }
+
+    #[test]
+    fn overall_budget_is_shared() {
+        let limiter = RateLimiter::new(RateLimits {
+            overall: Some(RateLimit::new(1.0, 1)),
+            reads: Some(RateLimit::new(100.0, 100)),
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve(MessageKind::GetSlot, now), Duration::ZERO);
+        assert_eq!(
+            limiter.reserve(MessageKind::AddSlot, now),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn records_waits() {
+        let limiter = RateLimiter::new(RateLimits {
+            overall: Some(RateLimit::new(100.0, 1)),
+            ..Default::default()
+        });
+
+        limiter.take(MessageKind::GetSlot, Duration::ZERO);
+        limiter.take(MessageKind::GetSlot, Duration::from_millis(10));
+
+        let stats = limiter.stats();
+        assert_eq!(stats.messages, 2);
+        assert_eq!(stats.delayed, 1);
+        assert_eq!(stats.longest_wait, Duration::from_millis(10));
+        assert_eq!(stats.total_wait, stats.longest_wait);
+        assert_eq!(stats.average_wait(), stats.total_wait / 2);
+    }
+
+    #[test]
+    fn checking_the_budget_does_not_take_it() {
+        let limiter = RateLimiter::new(RateLimits {
+            overall: Some(RateLimit::new(1.0, 1)),
+            ..Default::default()
+        });
+
+        assert!(limiter.ready_at(MessageKind::GetSlot) <= Instant::now());
+        assert!(limiter.ready_at(MessageKind::GetSlot) <= Instant::now());
+        limiter.take(MessageKind::GetSlot, Duration::ZERO);
+        assert!(limiter.ready_at(MessageKind::GetSlot) > Instant::now());
+    }
+}
@@ -0,0 +1,111 @@
+use rand::random;
+use std::time::Duration;
+
+/// Controls how a [`Client`](crate::Client) re-establishes a dropped connection.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Delay before the first reconnection attempt.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between attempts.
+    pub max_backoff: Duration,
+    /// Factor the delay grows by after every failed attempt.
+    pub multiplier: f64,
+    /// Number of attempts before giving up and closing the client, `None` retries forever.
+    pub max_attempts: Option<u32>,
+    /// Fraction of each delay that is randomized, from 0 (none) to 1 (up to the full delay).
+    pub jitter: f64,
+    /// What happens to requests made while the connection is down.
+    pub outage: OutageBehavior,
+}
+
+/// How requests sent during a reconnection are treated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutageBehavior {
+    /// Requests are queued and sent once the connection is re-established,
+    /// they still fail if their timeout elapses first.
+    Hold,
+    /// Requests immediately fail with [`ClientError::Disconnected`](crate::ClientError::Disconnected).
+    Reject,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            max_attempts: None,
+            jitter: 0.2,
+            outage: OutageBehavior::Hold,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// The delay before the given attempt, starting at 0, without jitter applied.
+    pub fn base_backoff(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.max(1.0).powi(attempt.min(i32::MAX as u32) as i32);
+        self.initial_backoff
+            .mul_f64(factor.min(u32::MAX as f64))
+            .min(self.max_backoff)
+    }
+
+    /// The delay before the given attempt, starting at 0, with jitter applied.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let base = self.base_backoff(attempt);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        // Spread the delay evenly across [base * (1 - jitter), base * (1 + jitter)].
+        base.mul_f64(1.0 - jitter + 2.0 * jitter * random::<f64>())
+    }
+
+    pub(crate) fn is_exhausted(&self, attempts: u32) -> bool {
+        self.max_attempts.is_some_and(|max| attempts >= max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> ReconnectPolicy {
+        ReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            multiplier: 2.0,
+            max_attempts: Some(3),
+            jitter: 0.0,
+            outage: OutageBehavior::Hold,
+        }
+    }
+
+    #[test]
+    fn backoff_grows_until_max() {
+        let policy = policy();
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(10), Duration::from_secs(1));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn jitter_stays_in_range() {
+        let policy = ReconnectPolicy {
+            jitter: 0.5,
+            ..policy()
+        };
+        for _ in 0..100 {
+            let delay = policy.backoff(1);
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(300));
+        }
+    }
+
+    #[test]
+    fn attempts_are_limited() {
+        let policy = policy();
+        assert!(!policy.is_exhausted(2));
+        assert!(policy.is_exhausted(3));
+        assert!(!ReconnectPolicy::default().is_exhausted(u32::MAX));
+    }
+}
@@ -0,0 +1,147 @@
+use crate::messages::MessageKind;
+use crate::responses::Response;
+use thiserror::Error;
+
+/// A failure reported by Resonite, a response with `success: false`.
+#[derive(Error, Clone, PartialEq, Eq, Debug)]
+#[error("{request_kind} failed ({kind:?}): {}", .error_info.as_deref().unwrap_or("no error info was provided"))]
+pub struct RemoteError {
+    /// The id of the message that failed.
+    pub source_message_id: Option<String>,
+    /// The kind of message that failed.
+    pub request_kind: MessageKind,
+    /// A best effort classification of `error_info`.
+    pub kind: RemoteErrorKind,
+    /// The error message provided by Resonite.
+    pub error_info: Option<String>,
+}
+
+/// What kind of failure Resonite reported, guessed from the error message.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RemoteErrorKind {
+    /// The slot, component or other referenced entity does not exist.
+    NotFound,
+    /// A type was unknown or not valid for where it was used.
+    InvalidType,
+    /// The operation is not allowed.
+    Permission,
+    /// The error did not match any known kind.
+    Unknown,
+}
+
+impl RemoteError {
+    pub fn new(response: Response, request_kind: MessageKind) -> Self {
+        Self {
+            kind: RemoteErrorKind::classify(response.error_info.as_deref()),
+            source_message_id: response.source_message_id,
+            request_kind,
+            error_info: response.error_info,
+        }
+    }
+}
+
+impl RemoteErrorKind {
+    pub fn classify(error_info: Option<&str>) -> Self {
+        let Some(error_info) = error_info else {
+            return RemoteErrorKind::Unknown;
+        };
+        let error_info = error_info.to_lowercase();
+        let contains_any = |needles: &[&str]| needles.iter().any(|n| error_info.contains(n));
+
+        if contains_any(&["permission", "not allowed", "denied", "unauthorized"]) {
+            RemoteErrorKind::Permission
+        } else if error_info.contains("type")
+            && contains_any(&[
+                "invalid",
+                "unknown",
+                "could not",
+                "couldn't",
+                "cannot",
+                "not supported",
+                "unsupported",
+                "not a valid",
+                "mismatch",
+            ])
+        {
+            RemoteErrorKind::InvalidType
+        } else if contains_any(&[
+            "not found",
+            "does not exist",
+            "doesn't exist",
+            "no such",
+            "could not find",
+            "couldn't find",
+        ]) {
+            RemoteErrorKind::NotFound
+        } else {
+            RemoteErrorKind::Unknown
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::responses::ResponseKind;
+
+    #[test]
+    fn classifies_not_found() {
+        assert_eq!(
+            RemoteErrorKind::classify(Some("Slot with ID Reso_1 not found")),
+            RemoteErrorKind::NotFound
+        );
+        assert_eq!(
+            RemoteErrorKind::classify(Some("Component doesn't exist")),
+            RemoteErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn classifies_invalid_type() {
+        assert_eq!(
+            RemoteErrorKind::classify(Some("Could not find type FrooxEngine.Taco")),
+            RemoteErrorKind::InvalidType
+        );
+        assert_eq!(
+            RemoteErrorKind::classify(Some("Invalid component type")),
+            RemoteErrorKind::InvalidType
+        );
+    }
+
+    #[test]
+    fn classifies_permission() {
+        assert_eq!(
+            RemoteErrorKind::classify(Some("Permission denied")),
+            RemoteErrorKind::Permission
+        );
+    }
+
+    #[test]
+    fn classifies_unknown() {
+        assert_eq!(
+            RemoteErrorKind::classify(Some("Bad things!")),
+            RemoteErrorKind::Unknown
+        );
+        assert_eq!(RemoteErrorKind::classify(None), RemoteErrorKind::Unknown);
+    }
+
+    #[test]
+    fn keeps_response_details() {
+        let error = RemoteError::new(
+            Response {
+                kind: ResponseKind::Response,
+                source_message_id: Some("Taco".into()),
+                success: false,
+                error_info: Some("Slot not found".into()),
+                diagnostics: Vec::new(),
+            },
+            MessageKind::GetSlot,
+        );
+        assert_eq!(error.source_message_id.as_deref(), Some("Taco"));
+        assert_eq!(error.kind, RemoteErrorKind::NotFound);
+        assert_eq!(
+            error.to_string(),
+            "getSlot failed (NotFound): Slot not found"
+        );
+    }
+}
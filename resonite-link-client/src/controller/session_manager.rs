@@ -0,0 +1,326 @@
+use crate::controller::{
+    Client, ClientConfig, ClientError, ConnectionEvent, Connector, DisconnectReason,
+    WebSocketConnector,
+};
+use crate::messages::Message;
+use crate::requests::Request;
+use crate::responses::Response;
+use log::warn;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("there is no session named {0}")]
+    UnknownSession(String),
+    #[error(transparent)]
+    Client(#[from] ClientError),
+}
+
+/// How a session's connection is doing, see [`SessionManager::health`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionHealth {
+    Connected,
+    /// The connection was lost, and is being re-established if the client's policy allows it.
+    Reconnecting {
+        attempt: u32,
+    },
+    /// The client is closed, and fails every request.
+    Closed,
+}
+
+/// Clients for several Resonite sessions, each known by a name.
+///
+/// Every client keeps its own connection, so each one reconnects according to its own
+/// [`ClientConfig::reconnect`] without affecting the others.
+#[derive(Default)]
+pub struct SessionManager {
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+struct Session {
+    client: Client,
+    health: Arc<Mutex<SessionHealth>>,
+    monitor: JoinHandle<()>,
+}
+
+impl Session {
+    fn new(client: Client) -> Self {
+        let health = Arc::new(Mutex::new(SessionHealth::Connected));
+        let reconnects = client
+            .reconnect_policy()
+            .is_some_and(|policy| !policy.is_exhausted(0));
+        let mut events = client.subscribe_events();
+        let monitor = tokio::spawn({
+            let health = health.clone();
+            async move {
+                loop {
+                    match events.recv().await {
+                        Ok(event) => {
+                            if let Some(next) = health_after(&event, reconnects) {
+                                *health.lock().unwrap() = next;
+                            }
+                        }
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return,
+                    }
+                }
+            }
+        });
+        Self {
+            client,
+            health,
+            monitor,
+        }
+    }
+
+    fn health(&self) -> SessionHealth {
+        // A client that gives up without reconnecting does not report it.
+        if self.client.is_closed() {
+            SessionHealth::Closed
+        } else {
+            *self.health.lock().unwrap()
+        }
+    }
+}
+
+/// The health of a session after an event, `None` if the event does not change it.
+fn health_after(event: &ConnectionEvent, reconnects: bool) -> Option<SessionHealth> {
+    match event {
+        ConnectionEvent::Connected => Some(SessionHealth::Connected),
+        ConnectionEvent::Reconnecting { attempt, .. } => {
+            Some(SessionHealth::Reconnecting { attempt: *attempt })
+        }
+        // Without reconnecting, the client closes once the connection is lost.
+        ConnectionEvent::Disconnected(DisconnectReason::ConnectionLost(_)) if reconnects => {
+            Some(SessionHealth::Reconnecting { attempt: 0 })
+        }
+        ConnectionEvent::Disconnected(_) => Some(SessionHealth::Closed),
+        _ => None,
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.monitor.abort();
+    }
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connects a new session, replacing and closing any session with the same name.
+    pub async fn connect(
+        &self,
+        name: impl Into<String>,
+        address: &str,
+        config: ClientConfig,
+    ) -> Result<Client, ClientError> {
+        self.connect_with(name, WebSocketConnector::new(address), config)
+            .await
+    }
+
+    /// Connects a new session with a custom connector, see [`Client::connect_with`].
+    pub async fn connect_with(
+        &self,
+        name: impl Into<String>,
+        connector: impl Connector,
+        config: ClientConfig,
+    ) -> Result<Client, ClientError> {
+        let client = Client::connect_with(connector, config).await?;
+        let name = name.into();
+        if let Some(replaced) = self.insert(name.clone(), client.clone())
+            && let Err(e) = replaced.close().await
+        {
+            warn!("the replaced client of session {} failed: {}", name, e);
+        }
+        Ok(client)
+    }
+
+    /// Adds an already connected client, returning the client it replaced.
+    /// The replaced client stays open while other handles to it remain.
+    pub fn insert(&self, name: impl Into<String>, client: Client) -> Option<Client> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(name.into(), Session::new(client))
+            .map(|session| session.client.clone())
+    }
+
+    /// Stops managing a session, returning its client without closing it.
+    pub fn remove(&self, name: &str) -> Option<Client> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .remove(name)
+            .map(|session| session.client.clone())
+    }
+
+    /// A handle to the session's client, for its typed requests or events.
+    pub fn client(&self, name: &str) -> Result<Client, SessionError> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .get(name)
+            .map(|session| session.client.clone())
+            .ok_or_else(|| SessionError::UnknownSession(name.to_owned()))
+    }
+
+    /// The names of every session, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.sessions.lock().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Sends a message to the named session, see [`Client::send`].
+    pub async fn send(&self, name: &str, message: Message) -> Result<Response, SessionError> {
+        Ok(self.client(name)?.send(message).await?)
+    }
+
+    /// Sends a typed request to the named session, see [`Client::request`].
+    pub async fn request<R: Request>(
+        &self,
+        name: &str,
+        request: R,
+    ) -> Result<R::Output, SessionError> {
+        Ok(self.client(name)?.request(request).await?)
+    }
+
+    /// The health of every session by name.
+    pub fn health(&self) -> BTreeMap<String, SessionHealth> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, session)| (name.clone(), session.health()))
+            .collect()
+    }
+
+    /// Whether every session is connected, which is also true without any sessions.
+    pub fn all_connected(&self) -> bool {
+        self.health()
+            .values()
+            .all(|health| *health == SessionHealth::Connected)
+    }
+
+    /// Closes and removes every session, reporting the first failure after closing the rest.
+    pub async fn close_all(&self) -> Result<(), ClientError> {
+        let sessions: Vec<_> = self.sessions.lock().unwrap().drain().collect();
+        let mut result = Ok(());
+        for (_, session) in sessions {
+            let closed = session.client.clone().close().await;
+            if result.is_ok() {
+                result = closed;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_model::Slot;
+    use crate::requests;
+    use crate::test_support::{Simulator, SimulatorServer, World};
+    use std::time::Duration;
+
+    async fn serve(root: &str) -> SimulatorServer {
+        Simulator::new(World::new(Slot {
+            id: root.into(),
+            ..Default::default()
+        }))
+        .listen("127.0.0.1:0")
+        .await
+        .unwrap()
+    }
+
+    fn get_root(slot_id: &str) -> requests::GetSlot {
+        requests::GetSlot {
+            slot_id: slot_id.into(),
+            depth: 0,
+            include_component_data: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn routes_requests_by_name() {
+        let (taco, burrito) = (serve("TacoRoot").await, serve("BurritoRoot").await);
+        let manager = SessionManager::new();
+        manager
+            .connect("taco", &taco.url(), Default::default())
+            .await
+            .unwrap();
+        manager
+            .connect("burrito", &burrito.url(), Default::default())
+            .await
+            .unwrap();
+
+        let root = manager.request("taco", get_root("TacoRoot")).await.unwrap();
+        assert_eq!(root.id, "TacoRoot");
+        let missing = manager.request("burrito", get_root("TacoRoot")).await;
+        assert!(matches!(
+            missing,
+            Err(SessionError::Client(ClientError::Remote(_)))
+        ));
+        let unknown = manager.request("nacho", get_root("TacoRoot")).await;
+        assert!(matches!(unknown, Err(SessionError::UnknownSession(name)) if name == "nacho"));
+
+        assert_eq!(manager.names(), vec!["burrito", "taco"]);
+        manager.close_all().await.unwrap();
+        assert!(manager.names().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reports_health_per_session() {
+        let (taco, burrito) = (serve("Root").await, serve("Root").await);
+        let manager = SessionManager::new();
+        manager
+            .connect("taco", &taco.url(), Default::default())
+            .await
+            .unwrap();
+        let burrito_client = manager
+            .connect("burrito", &burrito.url(), Default::default())
+            .await
+            .unwrap();
+        assert!(manager.all_connected());
+
+        burrito_client.close().await.unwrap();
+
+        assert!(!manager.all_connected());
+        assert_eq!(
+            manager.health(),
+            BTreeMap::from([
+                ("burrito".to_owned(), SessionHealth::Closed),
+                ("taco".to_owned(), SessionHealth::Connected),
+            ])
+        );
+        manager.close_all().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sessions_without_reconnection_close_when_lost() {
+        let (client_end, server_end) = crate::MemoryTransport::pair();
+        let manager = SessionManager::new();
+        manager.insert(
+            "taco",
+            Client::with_transport(client_end, Default::default()),
+        );
+
+        drop(server_end);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Checked without the closed client overriding it.
+        let sessions = manager.sessions.lock().unwrap();
+        assert_eq!(
+            *sessions["taco"].health.lock().unwrap(),
+            SessionHealth::Closed
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use crate::controller::ClientError;
+use futures_util::future::BoxFuture;
+use futures_util::{FutureExt, Sink, Stream};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::{Error, Message};
+
+#[cfg(feature = "rustls")]
+use rustls::pki_types::CertificateDer;
+
+/// A bidirectional stream of websocket messages that a [`Client`](crate::Client) can run over.
+///
+/// This is implemented for anything that is both a sink and a stream of tungstenite messages,
+/// such as a `WebSocketStream` over any socket, or a [`MemoryTransport`].
+pub trait Transport:
+    Sink<Message, Error = Error> + Stream<Item = Result<Message, Error>> + Unpin + Send + 'static
+{
+}
+
+impl<T> Transport for T where
+    T: Sink<Message, Error = Error>
+        + Stream<Item = Result<Message, Error>>
+        + Unpin
+        + Send
+        + 'static
+{
+}
+
+pub type BoxTransport = Pin<Box<dyn Transport>>;
+
+/// Opens new transports, used for the first connection and for every reconnection attempt.
+///
+/// This is implemented for closures returning a future that resolves to a transport.
+pub trait Connector: Send + Sync + 'static {
+    fn connect(&self) -> BoxFuture<'static, Result<BoxTransport, ClientError>>;
+}
+
+impl<F, Fut, T> Connector for F
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, ClientError>> + Send + 'static,
+    T: Transport,
+{
+    fn connect(&self) -> BoxFuture<'static, Result<BoxTransport, ClientError>> {
+        self()
+            .map(|transport| transport.map(|t| Box::pin(t) as BoxTransport))
+            .boxed()
+    }
+}
+
+/// Connects to a `ws://` or `wss://` address, `wss://` requires the `rustls` feature.
+///
+/// The settings are used for every connection it opens, including reconnections.
+#[derive(Clone, Debug)]
+pub struct WebSocketConnector {
+    address: String,
+    headers: Vec<(String, String)>,
+    websocket: WebSocketConfig,
+    nodelay: bool,
+    connect_timeout: Option<Duration>,
+    #[cfg(feature = "rustls")]
+    root_certificates: Vec<CertificateDer<'static>>,
+}
+
+impl WebSocketConnector {
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            headers: Vec::new(),
+            websocket: WebSocketConfig::default(),
+            nodelay: false,
+            connect_timeout: None,
+            #[cfg(feature = "rustls")]
+            root_certificates: Vec::new(),
+        }
+    }
+
+    /// Adds a header to the opening HTTP request, an invalid header fails the connection.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// The largest frame accepted from the server, `None` is unlimited.
+    /// Defaults to 16 MiB.
+    pub fn max_frame_size(mut self, size: Option<usize>) -> Self {
+        self.websocket.max_frame_size = size;
+        self
+    }
+
+    /// The largest message accepted from the server, `None` is unlimited.
+    /// Defaults to 64 MiB, which large trees fetched with a depth of `-1` can exceed.
+    pub fn max_message_size(mut self, size: Option<usize>) -> Self {
+        self.websocket.max_message_size = size;
+        self
+    }
+
+    /// Sets `TCP_NODELAY`, sending small messages right away instead of batching them.
+    pub fn nodelay(mut self, nodelay: bool) -> Self {
+        self.nodelay = nodelay;
+        self
+    }
+
+    /// How long to wait for the connection and websocket handshake, forever by default.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Trusts a certificate authority for `wss://` connections, on top of the Mozilla roots.
+    #[cfg(feature = "rustls")]
+    pub fn add_root_certificate(mut self, certificate: CertificateDer<'static>) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Trusts every certificate authority in a PEM file, see
+    /// [`WebSocketConnector::add_root_certificate`].
+    #[cfg(feature = "rustls")]
+    pub fn add_root_certificates_pem(
+        mut self,
+        pem: &[u8],
+    ) -> Result<Self, rustls::pki_types::pem::Error> {
+        use rustls::pki_types::pem::PemObject;
+        for certificate in CertificateDer::pem_slice_iter(pem) {
+            self.root_certificates.push(certificate?);
+        }
+        Ok(self)
+    }
+
+    fn request(&self) -> Result<tungstenite::handshake::client::Request, Error> {
+        let mut request = self.address.as_str().into_client_request()?;
+        for (name, value) in &self.headers {
+            let name = HeaderName::try_from(name).map_err(|e| Error::HttpFormat(e.into()))?;
+            let value = HeaderValue::try_from(value).map_err(|e| Error::HttpFormat(e.into()))?;
+            request.headers_mut().append(name, value);
+        }
+        Ok(request)
+    }
+
+    /// The TLS connector, `None` uses tungstenite's default.
+    #[cfg(feature = "rustls")]
+    fn tls(&self) -> Result<Option<tokio_tungstenite::Connector>, Error> {
+        use tungstenite::error::TlsError;
+        if self.root_certificates.is_empty() {
+            return Ok(None);
+        }
+        let mut roots = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        for certificate in &self.root_certificates {
+            roots
+                .add(certificate.clone())
+                .map_err(|e| Error::Tls(TlsError::Rustls(Box::new(e))))?;
+        }
+        let config = rustls::ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        Ok(Some(tokio_tungstenite::Connector::Rustls(
+            std::sync::Arc::new(config),
+        )))
+    }
+}
+
+impl Connector for WebSocketConnector {
+    fn connect(&self) -> BoxFuture<'static, Result<BoxTransport, ClientError>> {
+        let connector = self.clone();
+        async move {
+            let request = connector.request().map_err(ClientError::FailureToConnect)?;
+            #[cfg(not(feature = "rustls"))]
+            let connecting = tokio_tungstenite::connect_async_with_config(
+                request,
+                Some(connector.websocket),
+                connector.nodelay,
+            );
+            #[cfg(feature = "rustls")]
+            let connecting = tokio_tungstenite::connect_async_tls_with_config(
+                request,
+                Some(connector.websocket),
+                connector.nodelay,
+                connector.tls().map_err(ClientError::FailureToConnect)?,
+            );
+            let connected = match connector.connect_timeout {
+                Some(timeout) => tokio::time::timeout(timeout, connecting)
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(Error::Io(std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            format!("the connection was not established within {:?}", timeout),
+                        )))
+                    }),
+                None => connecting.await,
+            };
+            let (ws, _) = connected.map_err(ClientError::FailureToConnect)?;
+            Ok(Box::pin(ws) as BoxTransport)
+        }
+        .boxed()
+    }
+}
+
+/// One end of an in memory transport, everything sent is received by the other end.
+/// Dropping or closing one end ends the stream of the other.
+pub struct MemoryTransport {
+    sender: Option<mpsc::UnboundedSender<Message>>,
+    receiver: mpsc::UnboundedReceiver<Message>,
+}
+
+impl MemoryTransport {
+    /// Creates both connected ends of a transport.
+    pub fn pair() -> (Self, Self) {
+        let (left_sender, right_receiver) = mpsc::unbounded_channel();
+        let (right_sender, left_receiver) = mpsc::unbounded_channel();
+        (
+            Self {
+                sender: Some(left_sender),
+                receiver: left_receiver,
+            },
+            Self {
+                sender: Some(right_sender),
+                receiver: right_receiver,
+            },
+        )
+    }
+}
+
+impl Stream for MemoryTransport {
+    type Item = Result<Message, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx).map(|msg| msg.map(Ok))
+    }
+}
+
+impl Sink<Message> for MemoryTransport {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        match &self.sender {
+            Some(sender) if !sender.is_closed() => Poll::Ready(Ok(())),
+            _ => Poll::Ready(Err(Error::AlreadyClosed)),
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Message) -> Result<(), Error> {
+        self.sender
+            .as_ref()
+            .ok_or(Error::AlreadyClosed)?
+            .send(item)
+            .map_err(|_| Error::ConnectionClosed)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.sender = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+
+    #[tokio::test]
+    async fn memory_pair_is_connected() {
+        let (mut left, mut right) = MemoryTransport::pair();
+
+        left.send(Message::Text("Taco".into())).await.unwrap();
+        assert_eq!(
+            right.next().await.unwrap().unwrap(),
+            Message::Text("Taco".into())
+        );
+
+        right.close().await.unwrap();
+        assert!(left.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn memory_send_fails_when_other_end_dropped() {
+        let (mut left, right) = MemoryTransport::pair();
+        drop(right);
+        assert!(left.send(Message::Text("Taco".into())).await.is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::controller::{Client, ClientError};
+use crate::data_model::{Component, ComponentPatch, Slot, SlotPatch};
+use crate::requests::{self, Request};
+use crate::responses::ParseDiagnostic;
+use std::time::Duration;
+
+impl Client {
+    /// Sends a typed request using the default timeout, and converts the response.
+    /// A response of an unexpected kind fails with [`ClientError::UnexpectedResponse`].
+    pub async fn request<R: Request>(&self, request: R) -> Result<R::Output, ClientError> {
+        self.request_with_timeout(request, self.default_timeout())
+            .await
+    }
+
+    /// Sends a typed request overriding the default timeout, see [`Client::send_with_timeout`].
+    pub async fn request_with_timeout<R: Request>(
+        &self,
+        request: R,
+        timeout: Option<Duration>,
+    ) -> Result<R::Output, ClientError> {
+        self.request_with_diagnostics(request, timeout)
+            .await
+            .map(|(output, _)| output)
+    }
+
+    /// Sends a typed request, also returning what was replaced by placeholders while parsing
+    /// the response with [`ParseMode::Lenient`](crate::ParseMode::Lenient).
+    pub async fn request_with_diagnostics<R: Request>(
+        &self,
+        request: R,
+        timeout: Option<Duration>,
+    ) -> Result<(R::Output, Vec<ParseDiagnostic>), ClientError> {
+        let response = self.send_with_timeout(request.into(), timeout).await?;
+        let output = R::from_response(response.kind).map_err(|received| {
+            ClientError::UnexpectedResponse {
+                expected: R::EXPECTED,
+                received,
+            }
+        })?;
+        Ok((output, response.diagnostics))
+    }
+
+    pub async fn get_slot(
+        &self,
+        slot_id: impl Into<String>,
+        depth: i32,
+        include_component_data: bool,
+    ) -> Result<Slot, ClientError> {
+        self.request(requests::GetSlot {
+            slot_id: slot_id.into(),
+            depth,
+            include_component_data,
+        })
+        .await
+    }
+
+    pub async fn add_slot(&self, slot: Slot) -> Result<Slot, ClientError> {
+        self.request(requests::AddSlot { data: slot }).await
+    }
+
+    /// Changes the fields set in the patch, a whole [`Slot`] sets every field.
+    pub async fn update_slot(&self, patch: impl Into<SlotPatch>) -> Result<Slot, ClientError> {
+        self.request(requests::UpdateSlot { data: patch.into() })
+            .await
+    }
+
+    pub async fn remove_slot(&self, slot_id: impl Into<String>) -> Result<(), ClientError> {
+        self.request(requests::RemoveSlot {
+            slot_id: slot_id.into(),
+        })
+        .await
+    }
+
+    pub async fn get_component(
+        &self,
+        component_id: impl Into<String>,
+    ) -> Result<Component, ClientError> {
+        self.request(requests::GetComponent {
+            component_id: component_id.into(),
+        })
+        .await
+    }
+
+    pub async fn add_component(
+        &self,
+        container_slot_id: impl Into<String>,
+        component: Component,
+    ) -> Result<Component, ClientError> {
+        self.request(requests::AddComponent {
+            container_slot_id: container_slot_id.into(),
+            data: component,
+        })
+        .await
+    }
+
+    /// Changes the members in the patch, a whole [`Component`] sets every member.
+    pub async fn update_component(
+        &self,
+        patch: impl Into<ComponentPatch>,
+    ) -> Result<Component, ClientError> {
+        self.request(requests::UpdateComponent { data: patch.into() })
+            .await
+    }
+
+    pub async fn remove_component(
+        &self,
+        component_id: impl Into<String>,
+    ) -> Result<(), ClientError> {
+        self.request(requests::RemoveComponent {
+            component_id: component_id.into(),
+        })
+        .await
+    }
+}
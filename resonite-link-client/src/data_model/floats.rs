@@ -166,7 +166,7 @@ impl<T: FloatPrim> Ser<T> {
             }
         }
 
-        deserializer.deserialize_any(FloatSerdeVisitor(PhantomData::<T>::default()))
+        deserializer.deserialize_any(FloatSerdeVisitor(PhantomData::<T>))
     }
 }
 
@@ -1,126 +1,349 @@
-use crate::data_model::{ArrayField, Empty, Field, F32, F64};
+use crate::data_model::enum_field::Enum;
 use crate::data_model::primitives::*;
 use crate::data_model::reference::Reference;
 use crate::data_model::sync_list::SyncList;
 use crate::data_model::sync_object::SyncObject;
-use serde::{Deserialize, Serialize};
-use crate::data_model::enum_field::Enum;
+use crate::data_model::{ArrayField, Empty, F32, F64, Field};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
-#[serde(rename_all = "camelCase", tag = "$type")]
-pub enum Member {
-    #[serde(rename = "reference")]
-    Reference(Reference),
-
-    #[serde(rename = "list")]
-    SyncList(SyncList),
-    #[serde(rename = "syncObject")]
-    SyncObject(SyncObject),
-
-    #[serde(rename = "empty")]
-    Empty(Empty),
-
-    // Char(r)?
-    #[serde(rename = "string")]
-    String(Field<Option<String>>),
-    #[serde(rename = "Uri")]
-    Uri(Field<Option<String>>),
-    #[serde(rename = "enum")]
-    Enum(Enum),
-
-    #[serde(rename = "byte")]
-    Byte(Field<u8>),
-    #[serde(rename = "ushort")]
-    UShort(Field<u16>),
-    #[serde(rename = "uint")]
-    UInt(Field<u32>),
-    #[serde(rename = "ulong")]
-    ULong(Field<u64>),
-
-    #[serde(rename = "sbyte")]
-    SByte(Field<i8>),
-    #[serde(rename = "short")]
-    Short(Field<i16>),
-    #[serde(rename = "int")]
-    Int(Field<i32>),
-    #[serde(rename = "int?")]
-    NullInt(Field<Option<i32>>),
-    #[serde(rename = "long")]
-    Long(Field<i64>),
-
-    #[serde(rename = "int2")]
-    Int2(Field<Int2>),
-    #[serde(rename = "int2?")]
-    NullInt2(Field<Option<Int2>>),
-    #[serde(rename = "int3")]
-    Int3(Field<Int3>),
-    #[serde(rename = "int4")]
-    Int4(Field<Int4>),
-
-    #[serde(rename = "float")]
-    Float(Field<F32>),
-    #[serde(rename = "float?")]
-    NullFloat(Field<Option<F32>>),
-    #[serde(rename = "double")]
-    Double(Field<F64>),
-
-    // decimal?
-    #[serde(rename = "bool")]
-    Bool(Field<bool>),
-    #[serde(rename = "bool?")]
-    NullBool(Field<Option<bool>>),
-
-    #[serde(rename = "color")]
-    Color(Field<Color>),
-
-    #[serde(rename = "colorX")]
-    ColorX(Field<ColorX>),
-    #[serde(rename = "colorX?")]
-    NullColorX(Field<Option<ColorX>>),
-
-    #[serde(rename = "color32")]
-    Color32(Field<Color32>),
-
-    // Vectors and matrices...
-    #[serde(rename = "float2")]
-    Float2(Field<Float2>),
-    #[serde(rename = "float3")]
-    Float3(Field<Float3>),
-    #[serde(rename = "float3[]")]
-    Float3Vec(ArrayField<Float3>),
-    #[serde(rename = "float3?")]
-    NullFloat3(Field<Option<Float3>>),
-    #[serde(rename = "float4")]
-    Float4(Field<Float4>),
-
-    #[serde(rename = "floatQ")]
-    FloatQ(Field<FloatQ>),
-    #[serde(rename = "floatQ?")]
-    NullFloatQ(Field<Option<FloatQ>>),
-    #[serde(rename = "floatQ[]")]
-    FloatQVec(ArrayField<FloatQ>),
-    // TODO: Implement more fields.
-}
+macro_rules! members {
+    ($($(#[$meta:meta])* $name:literal => $variant:ident($inner:ty),)+) => {
+        /// A member of a component or sync object, tagged by its Resonite type in `$type`.
+        ///
+        /// Value types are named after their Resonite type, with a `Null` prefix for `T?`
+        /// and a `Vec` suffix for `T[]`.
+        #[derive(Clone, PartialEq, Debug)]
+        pub enum Member {
+            $(
+                $(#[$meta])*
+                $variant($inner),
+            )+
+            /// A member with a `$type` this crate does not know yet, or one that failed to parse
+            /// with [`Response::from_str_lenient`](crate::Response::from_str_lenient).
+            ///
+            /// It is serialized as `raw`, unchanged, so it survives being sent back in an update.
+            Unknown {
+                type_name: String,
+                id: String,
+                /// The whole member as received, including `$type` and `id`.
+                raw: Value,
+            },
+        }
 
-macro_rules! match_id {
-    ($target:ident, $($field:ident),+ $(,)?) => {
-        match $target {
+        #[derive(Serialize)]
+        #[serde(tag = "$type")]
+        enum KnownRef<'a> {
             $(
-                $field(f) => f.id(),
+                #[serde(rename = $name)]
+                $variant(&'a $inner),
             )+
         }
+
+        #[derive(Deserialize)]
+        #[serde(tag = "$type")]
+        enum Known {
+            $(
+                #[serde(rename = $name)]
+                $variant($inner),
+            )+
+        }
+
+        impl Serialize for Member {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    $(
+                        Member::$variant(member) => KnownRef::$variant(member).serialize(serializer),
+                    )+
+                    Member::Unknown { raw, .. } => raw.serialize(serializer),
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Member {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let raw = Value::deserialize(deserializer)?;
+                match raw.get("$type").and_then(Value::as_str) {
+                    $(Some($name))|+ | None => {}
+                    Some(type_name) => {
+                        return Ok(Member::Unknown {
+                            type_name: type_name.to_owned(),
+                            id: raw.get("id").and_then(Value::as_str).unwrap_or_default().to_owned(),
+                            raw,
+                        });
+                    }
+                }
+                Ok(match Known::deserialize(raw).map_err(D::Error::custom)? {
+                    $(
+                        Known::$variant(member) => Member::$variant(member),
+                    )+
+                })
+            }
+        }
+
+        impl super::ID for Member {
+            fn id(&self) -> &str {
+                match self {
+                    $(
+                        Member::$variant(member) => member.id(),
+                    )+
+                    Member::Unknown { id, .. } => id,
+                }
+            }
+        }
     };
 }
 
-impl super::ID for Member {
-    fn id(&self) -> &str {
-        use Member::*;
-        match_id!(
-            self, Reference, SyncList, SyncObject, String, Uri, Enum, Byte, UShort, UInt, ULong,
-            SByte, Short, NullInt, Int, NullInt2, Int2, Float3Vec, Int3, Int4, Long, Float, Float3, NullFloat3, FloatQ, Float2, Float4,
-            NullFloatQ, NullFloat, FloatQVec, Empty, Double, Bool, NullBool, Color, ColorX, Color32, NullColorX
-        )
-    }
+members! {
+    "reference" => Reference(Reference),
+    "list" => SyncList(SyncList),
+    "syncObject" => SyncObject(SyncObject),
+    "empty" => Empty(Empty),
+    "enum" => Enum(Enum),
+    "string" => String(Field<Option<String>>),
+    "string[]" => StringVec(ArrayField<Option<String>>),
+    "Uri" => Uri(Field<Option<String>>),
+    "Uri[]" => UriVec(ArrayField<Option<String>>),
+
+    "bool" => Bool(Field<bool>),
+    "bool?" => NullBool(Field<Option<bool>>),
+    "bool[]" => BoolVec(ArrayField<bool>),
+
+    "byte" => Byte(Field<u8>),
+    "byte?" => NullByte(Field<Option<u8>>),
+    "byte[]" => ByteVec(ArrayField<u8>),
+
+    "ushort" => UShort(Field<u16>),
+    "ushort?" => NullUShort(Field<Option<u16>>),
+    "ushort[]" => UShortVec(ArrayField<u16>),
+
+    "uint" => UInt(Field<u32>),
+    "uint?" => NullUInt(Field<Option<u32>>),
+    "uint[]" => UIntVec(ArrayField<u32>),
+
+    "ulong" => ULong(Field<u64>),
+    "ulong?" => NullULong(Field<Option<u64>>),
+    "ulong[]" => ULongVec(ArrayField<u64>),
+
+    "sbyte" => SByte(Field<i8>),
+    "sbyte?" => NullSByte(Field<Option<i8>>),
+    "sbyte[]" => SByteVec(ArrayField<i8>),
+
+    "short" => Short(Field<i16>),
+    "short?" => NullShort(Field<Option<i16>>),
+    "short[]" => ShortVec(ArrayField<i16>),
+
+    "int" => Int(Field<i32>),
+    "int?" => NullInt(Field<Option<i32>>),
+    "int[]" => IntVec(ArrayField<i32>),
+
+    "long" => Long(Field<i64>),
+    "long?" => NullLong(Field<Option<i64>>),
+    "long[]" => LongVec(ArrayField<i64>),
+
+    "float" => Float(Field<F32>),
+    "float?" => NullFloat(Field<Option<F32>>),
+    "float[]" => FloatVec(ArrayField<F32>),
+
+    "double" => Double(Field<F64>),
+    "double?" => NullDouble(Field<Option<F64>>),
+    "double[]" => DoubleVec(ArrayField<F64>),
+
+    /// A C# `decimal`, which loses precision beyond what a double can represent.
+    "decimal" => Decimal(Field<F64>),
+    "decimal?" => NullDecimal(Field<Option<F64>>),
+    "decimal[]" => DecimalVec(ArrayField<F64>),
+
+    "char" => Char(Field<char>),
+    "char?" => NullChar(Field<Option<char>>),
+    "char[]" => CharVec(ArrayField<char>),
+
+    /// An ISO 8601 date and time, such as `2025-01-31T12:00:00Z`.
+    "DateTime" => DateTime(Field<String>),
+    "DateTime?" => NullDateTime(Field<Option<String>>),
+    "DateTime[]" => DateTimeVec(ArrayField<String>),
+
+    /// A duration formatted as `[-][d.]hh:mm:ss[.fffffff]`, such as `1.02:03:04.5`.
+    "TimeSpan" => TimeSpan(Field<String>),
+    "TimeSpan?" => NullTimeSpan(Field<Option<String>>),
+    "TimeSpan[]" => TimeSpanVec(ArrayField<String>),
+
+    "color" => Color(Field<Color>),
+    "color?" => NullColor(Field<Option<Color>>),
+    "color[]" => ColorVec(ArrayField<Color>),
+
+    "colorX" => ColorX(Field<ColorX>),
+    "colorX?" => NullColorX(Field<Option<ColorX>>),
+    "colorX[]" => ColorXVec(ArrayField<ColorX>),
+
+    "color32" => Color32(Field<Color32>),
+    "color32?" => NullColor32(Field<Option<Color32>>),
+    "color32[]" => Color32Vec(ArrayField<Color32>),
+
+    "bool2" => Bool2(Field<Bool2>),
+    "bool2?" => NullBool2(Field<Option<Bool2>>),
+    "bool2[]" => Bool2Vec(ArrayField<Bool2>),
+
+    "bool3" => Bool3(Field<Bool3>),
+    "bool3?" => NullBool3(Field<Option<Bool3>>),
+    "bool3[]" => Bool3Vec(ArrayField<Bool3>),
+
+    "bool4" => Bool4(Field<Bool4>),
+    "bool4?" => NullBool4(Field<Option<Bool4>>),
+    "bool4[]" => Bool4Vec(ArrayField<Bool4>),
+
+    "byte2" => Byte2(Field<Byte2>),
+    "byte2?" => NullByte2(Field<Option<Byte2>>),
+    "byte2[]" => Byte2Vec(ArrayField<Byte2>),
+
+    "byte3" => Byte3(Field<Byte3>),
+    "byte3?" => NullByte3(Field<Option<Byte3>>),
+    "byte3[]" => Byte3Vec(ArrayField<Byte3>),
+
+    "byte4" => Byte4(Field<Byte4>),
+    "byte4?" => NullByte4(Field<Option<Byte4>>),
+    "byte4[]" => Byte4Vec(ArrayField<Byte4>),
+
+    "ushort2" => UShort2(Field<UShort2>),
+    "ushort2?" => NullUShort2(Field<Option<UShort2>>),
+    "ushort2[]" => UShort2Vec(ArrayField<UShort2>),
+
+    "ushort3" => UShort3(Field<UShort3>),
+    "ushort3?" => NullUShort3(Field<Option<UShort3>>),
+    "ushort3[]" => UShort3Vec(ArrayField<UShort3>),
+
+    "ushort4" => UShort4(Field<UShort4>),
+    "ushort4?" => NullUShort4(Field<Option<UShort4>>),
+    "ushort4[]" => UShort4Vec(ArrayField<UShort4>),
+
+    "uint2" => UInt2(Field<UInt2>),
+    "uint2?" => NullUInt2(Field<Option<UInt2>>),
+    "uint2[]" => UInt2Vec(ArrayField<UInt2>),
+
+    "uint3" => UInt3(Field<UInt3>),
+    "uint3?" => NullUInt3(Field<Option<UInt3>>),
+    "uint3[]" => UInt3Vec(ArrayField<UInt3>),
+
+    "uint4" => UInt4(Field<UInt4>),
+    "uint4?" => NullUInt4(Field<Option<UInt4>>),
+    "uint4[]" => UInt4Vec(ArrayField<UInt4>),
+
+    "ulong2" => ULong2(Field<ULong2>),
+    "ulong2?" => NullULong2(Field<Option<ULong2>>),
+    "ulong2[]" => ULong2Vec(ArrayField<ULong2>),
+
+    "ulong3" => ULong3(Field<ULong3>),
+    "ulong3?" => NullULong3(Field<Option<ULong3>>),
+    "ulong3[]" => ULong3Vec(ArrayField<ULong3>),
+
+    "ulong4" => ULong4(Field<ULong4>),
+    "ulong4?" => NullULong4(Field<Option<ULong4>>),
+    "ulong4[]" => ULong4Vec(ArrayField<ULong4>),
+
+    "sbyte2" => SByte2(Field<SByte2>),
+    "sbyte2?" => NullSByte2(Field<Option<SByte2>>),
+    "sbyte2[]" => SByte2Vec(ArrayField<SByte2>),
+
+    "sbyte3" => SByte3(Field<SByte3>),
+    "sbyte3?" => NullSByte3(Field<Option<SByte3>>),
+    "sbyte3[]" => SByte3Vec(ArrayField<SByte3>),
+
+    "sbyte4" => SByte4(Field<SByte4>),
+    "sbyte4?" => NullSByte4(Field<Option<SByte4>>),
+    "sbyte4[]" => SByte4Vec(ArrayField<SByte4>),
+
+    "short2" => Short2(Field<Short2>),
+    "short2?" => NullShort2(Field<Option<Short2>>),
+    "short2[]" => Short2Vec(ArrayField<Short2>),
+
+    "short3" => Short3(Field<Short3>),
+    "short3?" => NullShort3(Field<Option<Short3>>),
+    "short3[]" => Short3Vec(ArrayField<Short3>),
+
+    "short4" => Short4(Field<Short4>),
+    "short4?" => NullShort4(Field<Option<Short4>>),
+    "short4[]" => Short4Vec(ArrayField<Short4>),
+
+    "int2" => Int2(Field<Int2>),
+    "int2?" => NullInt2(Field<Option<Int2>>),
+    "int2[]" => Int2Vec(ArrayField<Int2>),
+
+    "int3" => Int3(Field<Int3>),
+    "int3?" => NullInt3(Field<Option<Int3>>),
+    "int3[]" => Int3Vec(ArrayField<Int3>),
+
+    "int4" => Int4(Field<Int4>),
+    "int4?" => NullInt4(Field<Option<Int4>>),
+    "int4[]" => Int4Vec(ArrayField<Int4>),
+
+    "long2" => Long2(Field<Long2>),
+    "long2?" => NullLong2(Field<Option<Long2>>),
+    "long2[]" => Long2Vec(ArrayField<Long2>),
+
+    "long3" => Long3(Field<Long3>),
+    "long3?" => NullLong3(Field<Option<Long3>>),
+    "long3[]" => Long3Vec(ArrayField<Long3>),
+
+    "long4" => Long4(Field<Long4>),
+    "long4?" => NullLong4(Field<Option<Long4>>),
+    "long4[]" => Long4Vec(ArrayField<Long4>),
+
+    "float2" => Float2(Field<Float2>),
+    "float2?" => NullFloat2(Field<Option<Float2>>),
+    "float2[]" => Float2Vec(ArrayField<Float2>),
+
+    "float3" => Float3(Field<Float3>),
+    "float3?" => NullFloat3(Field<Option<Float3>>),
+    "float3[]" => Float3Vec(ArrayField<Float3>),
+
+    "float4" => Float4(Field<Float4>),
+    "float4?" => NullFloat4(Field<Option<Float4>>),
+    "float4[]" => Float4Vec(ArrayField<Float4>),
+
+    "floatQ" => FloatQ(Field<FloatQ>),
+    "floatQ?" => NullFloatQ(Field<Option<FloatQ>>),
+    "floatQ[]" => FloatQVec(ArrayField<FloatQ>),
+
+    "double2" => Double2(Field<Double2>),
+    "double2?" => NullDouble2(Field<Option<Double2>>),
+    "double2[]" => Double2Vec(ArrayField<Double2>),
+
+    "double3" => Double3(Field<Double3>),
+    "double3?" => NullDouble3(Field<Option<Double3>>),
+    "double3[]" => Double3Vec(ArrayField<Double3>),
+
+    "double4" => Double4(Field<Double4>),
+    "double4?" => NullDouble4(Field<Option<Double4>>),
+    "double4[]" => Double4Vec(ArrayField<Double4>),
+
+    "doubleQ" => DoubleQ(Field<DoubleQ>),
+    "doubleQ?" => NullDoubleQ(Field<Option<DoubleQ>>),
+    "doubleQ[]" => DoubleQVec(ArrayField<DoubleQ>),
+
+    "float2x2" => Float2x2(Field<Float2x2>),
+    "float2x2?" => NullFloat2x2(Field<Option<Float2x2>>),
+    "float2x2[]" => Float2x2Vec(ArrayField<Float2x2>),
+
+    "float3x3" => Float3x3(Field<Float3x3>),
+    "float3x3?" => NullFloat3x3(Field<Option<Float3x3>>),
+    "float3x3[]" => Float3x3Vec(ArrayField<Float3x3>),
+
+    "float4x4" => Float4x4(Field<Float4x4>),
+    "float4x4?" => NullFloat4x4(Field<Option<Float4x4>>),
+    "float4x4[]" => Float4x4Vec(ArrayField<Float4x4>),
+
+    "double2x2" => Double2x2(Field<Double2x2>),
+    "double2x2?" => NullDouble2x2(Field<Option<Double2x2>>),
+    "double2x2[]" => Double2x2Vec(ArrayField<Double2x2>),
+
+    "double3x3" => Double3x3(Field<Double3x3>),
+    "double3x3?" => NullDouble3x3(Field<Option<Double3x3>>),
+    "double3x3[]" => Double3x3Vec(ArrayField<Double3x3>),
+
+    "double4x4" => Double4x4(Field<Double4x4>),
+    "double4x4?" => NullDouble4x4(Field<Option<Double4x4>>),
+    "double4x4[]" => Double4x4Vec(ArrayField<Double4x4>),
 }
 
 #[cfg(test)]
@@ -193,4 +416,223 @@ mod tests {
             }),
         );
     }
+
+    #[test]
+    fn nullable_bool2_null() {
+        assert_bi_eq_json(
+            Member::NullBool2(Field::new("Taco", None)),
+            json!({
+                "$type": "bool2?",
+                "id": "Taco",
+                "value": null,
+            }),
+        );
+    }
+
+    #[test]
+    fn double3() {
+        assert_bi_eq_json(
+            Member::Double3(Field::new(
+                "Taco",
+                Double3 {
+                    x: 1.0,
+                    y: f64::INFINITY,
+                    z: 3.5,
+                },
+            )),
+            json!({
+                "$type": "double3",
+                "id": "Taco",
+                "value": {
+                    "x": 1.0,
+                    "y": "Infinity",
+                    "z": 3.5,
+                },
+            }),
+        );
+    }
+
+    #[test]
+    fn long_array() {
+        assert_bi_eq_json(
+            Member::LongVec(ArrayField::new("Taco", vec![1, -2, i64::MAX])),
+            json!({
+                "$type": "long[]",
+                "id": "Taco",
+                "values": [1, -2, i64::MAX],
+            }),
+        );
+    }
+
+    #[test]
+    fn string_array() {
+        assert_bi_eq_json(
+            Member::StringVec(ArrayField::new("Taco", vec![Some("Burrito".into()), None])),
+            json!({
+                "$type": "string[]",
+                "id": "Taco",
+                "values": ["Burrito", null],
+            }),
+        );
+    }
+
+    #[test]
+    fn char() {
+        assert_bi_eq_json(
+            Member::Char(Field::new("Taco", 'T')),
+            json!({
+                "$type": "char",
+                "id": "Taco",
+                "value": "T",
+            }),
+        );
+    }
+
+    #[test]
+    fn decimal() {
+        assert_bi_eq_json(
+            Member::Decimal(Field::new("Taco", 1.25f64.into())),
+            json!({
+                "$type": "decimal",
+                "id": "Taco",
+                "value": 1.25,
+            }),
+        );
+    }
+
+    #[test]
+    fn date_time_and_time_span() {
+        assert_bi_eq_json(
+            Member::NullDateTime(Field::new("Taco", Some("2025-01-31T12:00:00Z".into()))),
+            json!({
+                "$type": "DateTime?",
+                "id": "Taco",
+                "value": "2025-01-31T12:00:00Z",
+            }),
+        );
+        assert_bi_eq_json(
+            Member::TimeSpan(Field::new("Taco", "1.02:03:04.5".into())),
+            json!({
+                "$type": "TimeSpan",
+                "id": "Taco",
+                "value": "1.02:03:04.5",
+            }),
+        );
+    }
+
+    #[test]
+    fn double_q_array() {
+        assert_bi_eq_json(
+            Member::DoubleQVec(ArrayField::new(
+                "Taco",
+                vec![DoubleQ {
+                    w: 1.0,
+                    ..Default::default()
+                }],
+            )),
+            json!({
+                "$type": "doubleQ[]",
+                "id": "Taco",
+                "values": [{ "x": 0.0, "y": 0.0, "z": 0.0, "w": 1.0 }],
+            }),
+        );
+    }
+
+    #[test]
+    fn float2x2() {
+        assert_bi_eq_json(
+            Member::Float2x2(Field::new(
+                "Taco",
+                Float2x2 {
+                    m00: 1.0,
+                    m01: 2.0,
+                    m10: 3.0,
+                    m11: 4.0,
+                },
+            )),
+            json!({
+                "$type": "float2x2",
+                "id": "Taco",
+                "value": { "m00": 1.0, "m01": 2.0, "m10": 3.0, "m11": 4.0 },
+            }),
+        );
+    }
+
+    #[test]
+    fn uint4() {
+        assert_bi_eq_json(
+            Member::UInt4(Field::new(
+                "Taco",
+                UInt4 {
+                    x: 1,
+                    y: 2,
+                    z: 3,
+                    w: u32::MAX,
+                },
+            )),
+            json!({
+                "$type": "uint4",
+                "id": "Taco",
+                "value": { "x": 1, "y": 2, "z": 3, "w": u32::MAX },
+            }),
+        );
+    }
+
+    #[test]
+    fn id_of_any_member() {
+        use crate::data_model::ID;
+        assert_eq!(Member::NullColor32(Field::new("Taco", None)).id(), "Taco");
+        assert_eq!(
+            Member::SByte4Vec(ArrayField::new("Burrito", vec![])).id(),
+            "Burrito"
+        );
+    }
+
+    #[test]
+    fn unknown_type_is_kept() {
+        let raw = json!({
+            "$type": "taco3",
+            "id": "Taco",
+            "value": { "shell": "hard" },
+        });
+        assert_bi_eq_json(
+            Member::Unknown {
+                type_name: "taco3".into(),
+                id: "Taco".into(),
+                raw: raw.clone(),
+            },
+            raw,
+        );
+    }
+
+    #[test]
+    fn unknown_type_in_a_sync_object() {
+        let member: Member = serde_json::from_value(json!({
+            "$type": "syncObject",
+            "id": "Taco",
+            "members": {
+                "Filling": { "$type": "burrito", "id": "Burrito" },
+                "Count": { "$type": "int", "id": "Count", "value": 3 },
+            },
+        }))
+        .unwrap();
+        let Member::SyncObject(object) = member else {
+            panic!("expected a sync object, got {:?}", member);
+        };
+        assert!(matches!(
+            &object.members["Filling"],
+            Member::Unknown { type_name, id, .. } if type_name == "burrito" && id == "Burrito"
+        ));
+        assert_eq!(object.members["Count"], Member::Int(Field::new("Count", 3)));
+    }
+
+    #[test]
+    fn known_type_with_bad_value_fails() {
+        let result = serde_json::from_value::<Member>(json!({
+            "$type": "int",
+            "id": "Taco",
+            "value": "Burrito",
+        }));
+        assert!(result.is_err());
+    }
 }
@@ -3,6 +3,7 @@ mod enum_field;
 mod field;
 mod floats;
 mod member;
+mod patch;
 mod primitives;
 mod reference;
 mod slot;
@@ -16,6 +17,7 @@ pub use enum_field::Enum;
 pub use field::Field;
 pub use floats::{F32, F64};
 pub use member::Member;
+pub use patch::{ComponentPatch, SlotPatch};
 pub use primitives::*;
 pub use reference::Reference;
 pub use slot::Slot;
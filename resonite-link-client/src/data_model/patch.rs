@@ -0,0 +1,210 @@
+use super::{Component, Field, ID, Member, Reference, Slot};
+use crate::data_model::primitives::{Float3, FloatQ};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The fields of a slot to change with [`Message::UpdateSlot`](crate::Message::UpdateSlot),
+/// fields left as `None` are not sent, and keep their current value.
+///
+/// A whole [`Slot`] converts into a patch setting all of its fields,
+/// except for a name or tag with a null value.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotPatch {
+    pub id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Reference>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<Field<Option<String>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<Field<Option<String>>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<Field<Float3>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<Field<FloatQ>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<Field<Float3>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<Field<bool>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_persistent: Option<Field<bool>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_offset: Option<Field<i64>>,
+}
+
+impl SlotPatch {
+    /// A patch of the slot that changes nothing yet.
+    pub fn new(slot_id: impl Into<String>) -> Self {
+        Self {
+            id: slot_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Moves the slot under another slot.
+    pub fn parent(mut self, parent_slot_id: impl Into<String>) -> Self {
+        self.parent = Some(Reference::new("", parent_slot_id, "FrooxEngine.Slot"));
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(Field::new("", Some(name.into())));
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(Field::new("", Some(tag.into())));
+        self
+    }
+
+    pub fn position(mut self, position: Float3) -> Self {
+        self.position = Some(Field::new("", position));
+        self
+    }
+
+    pub fn rotation(mut self, rotation: FloatQ) -> Self {
+        self.rotation = Some(Field::new("", rotation));
+        self
+    }
+
+    pub fn scale(mut self, scale: Float3) -> Self {
+        self.scale = Some(Field::new("", scale));
+        self
+    }
+
+    pub fn is_active(mut self, is_active: bool) -> Self {
+        self.is_active = Some(Field::new("", is_active));
+        self
+    }
+
+    pub fn is_persistent(mut self, is_persistent: bool) -> Self {
+        self.is_persistent = Some(Field::new("", is_persistent));
+        self
+    }
+
+    pub fn order_offset(mut self, order_offset: i64) -> Self {
+        self.order_offset = Some(Field::new("", order_offset));
+        self
+    }
+}
+
+impl From<Slot> for SlotPatch {
+    /// Sets every field of the slot, its components and children are not part of an update.
+    fn from(slot: Slot) -> Self {
+        Self {
+            id: slot.id,
+            parent: Some(slot.parent),
+            name: Some(slot.name),
+            tag: Some(slot.tag),
+            position: Some(slot.position),
+            rotation: Some(slot.rotation),
+            scale: Some(slot.scale),
+            is_active: Some(slot.is_active),
+            is_persistent: Some(slot.is_persistent),
+            order_offset: Some(slot.order_offset),
+        }
+    }
+}
+
+impl ID for SlotPatch {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// The members of a component to change with
+/// [`Message::UpdateComponent`](crate::Message::UpdateComponent),
+/// members that are not included keep their current value.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentPatch {
+    pub id: String,
+    /// When set, the update fails if the component is of another type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_type: Option<String>,
+    #[serde(default, deserialize_with = "crate::serde_helpers::null_to_default")]
+    pub members: HashMap<String, Member>,
+}
+
+impl ComponentPatch {
+    /// A patch of the component that changes nothing yet.
+    pub fn new(component_id: impl Into<String>) -> Self {
+        Self {
+            id: component_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets a member, replacing any value set for it before.
+    pub fn member(mut self, name: impl Into<String>, member: Member) -> Self {
+        self.members.insert(name.into(), member);
+        self
+    }
+}
+
+impl From<Component> for ComponentPatch {
+    /// Sets every member of the component, an empty component type is left out.
+    fn from(component: Component) -> Self {
+        Self {
+            id: component.id,
+            component_type: Some(component.component_type).filter(|t| !t.is_empty()),
+            members: component.members,
+        }
+    }
+}
+
+impl ID for ComponentPatch {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_bi_eq_json;
+    use serde_json::json;
+
+    #[test]
+    fn slot_patch_leaves_out_untouched_fields() {
+        assert_bi_eq_json(
+            SlotPatch::new("Taco").name("Burrito").is_active(false),
+            json!({
+                "id": "Taco",
+                "name": { "id": "", "value": "Burrito" },
+                "isActive": { "id": "", "value": false },
+            }),
+        );
+    }
+
+    #[test]
+    fn slot_converts_to_a_full_patch() {
+        let patch = SlotPatch::from(Slot {
+            id: "Taco".into(),
+            ..Default::default()
+        });
+        let value = serde_json::to_value(&patch).unwrap();
+        let fields = value.as_object().unwrap();
+        assert_eq!(fields.len(), 10);
+        assert!(!fields.contains_key("components"));
+        assert!(!fields.contains_key("children"));
+    }
+
+    #[test]
+    fn component_patch_only_has_changed_members() {
+        assert_bi_eq_json(
+            ComponentPatch::new("Taco").member("Shell", Member::Bool(Field::new("", true))),
+            json!({
+                "id": "Taco",
+                "members": {
+                    "Shell": { "$type": "bool", "id": "", "value": true },
+                },
+            }),
+        );
+    }
+}
@@ -1,69 +1,100 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
-pub struct Int2 {
-    pub x: i32,
-    pub y: i32,
+macro_rules! primitive {
+    ($name:ident, $t:ty, [$($field:ident),+ $(,)?]) => {
+        #[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+        pub struct $name {
+            $(
+                pub $field: $t,
+            )+
+        }
+    };
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
-pub struct Int3 {
-    pub x: i32,
-    pub y: i32,
-    pub z: i32,
+macro_rules! float_primitive {
+    ($name:ident, $t:ty, [$($field:ident),+ $(,)?]) => {
+        #[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+        pub struct $name {
+            $(
+                #[serde(with = "super::floats::Ser")]
+                pub $field: $t,
+            )+
+        }
+    };
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
-pub struct Int4 {
-    pub x: i32,
-    pub y: i32,
-    pub z: i32,
-    pub w: i32,
-}
+primitive!(Bool2, bool, [x, y]);
+primitive!(Bool3, bool, [x, y, z]);
+primitive!(Bool4, bool, [x, y, z, w]);
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
-pub struct Float2 {
-    #[serde(with = "super::floats::Ser")]
-    pub x: f32,
-    #[serde(with = "super::floats::Ser")]
-    pub y: f32,
-}
+primitive!(Byte2, u8, [x, y]);
+primitive!(Byte3, u8, [x, y, z]);
+primitive!(Byte4, u8, [x, y, z, w]);
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
-pub struct Float3 {
-    #[serde(with = "super::floats::Ser")]
-    pub x: f32,
-    #[serde(with = "super::floats::Ser")]
-    pub y: f32,
-    #[serde(with = "super::floats::Ser")]
-    pub z: f32,
-}
+primitive!(UShort2, u16, [x, y]);
+primitive!(UShort3, u16, [x, y, z]);
+primitive!(UShort4, u16, [x, y, z, w]);
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
-pub struct Float4 {
-    #[serde(with = "super::floats::Ser")]
-    pub x: f32,
-    #[serde(with = "super::floats::Ser")]
-    pub y: f32,
-    #[serde(with = "super::floats::Ser")]
-    pub z: f32,
-    #[serde(with = "super::floats::Ser")]
-    pub w: f32,
-}
+primitive!(UInt2, u32, [x, y]);
+primitive!(UInt3, u32, [x, y, z]);
+primitive!(UInt4, u32, [x, y, z, w]);
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
-pub struct FloatQ {
-    #[serde(with = "super::floats::Ser")]
-    pub x: f32,
-    #[serde(with = "super::floats::Ser")]
-    pub y: f32,
-    #[serde(with = "super::floats::Ser")]
-    pub z: f32,
-    #[serde(with = "super::floats::Ser")]
-    pub w: f32,
-}
+primitive!(ULong2, u64, [x, y]);
+primitive!(ULong3, u64, [x, y, z]);
+primitive!(ULong4, u64, [x, y, z, w]);
+
+primitive!(SByte2, i8, [x, y]);
+primitive!(SByte3, i8, [x, y, z]);
+primitive!(SByte4, i8, [x, y, z, w]);
+
+primitive!(Short2, i16, [x, y]);
+primitive!(Short3, i16, [x, y, z]);
+primitive!(Short4, i16, [x, y, z, w]);
+
+primitive!(Int2, i32, [x, y]);
+primitive!(Int3, i32, [x, y, z]);
+primitive!(Int4, i32, [x, y, z, w]);
+
+primitive!(Long2, i64, [x, y]);
+primitive!(Long3, i64, [x, y, z]);
+primitive!(Long4, i64, [x, y, z, w]);
+
+float_primitive!(Float2, f32, [x, y]);
+float_primitive!(Float3, f32, [x, y, z]);
+float_primitive!(Float4, f32, [x, y, z, w]);
+float_primitive!(FloatQ, f32, [x, y, z, w]);
+
+float_primitive!(Double2, f64, [x, y]);
+float_primitive!(Double3, f64, [x, y, z]);
+float_primitive!(Double4, f64, [x, y, z, w]);
+float_primitive!(DoubleQ, f64, [x, y, z, w]);
+
+// Row major, m01 is the first row and second column.
+float_primitive!(Float2x2, f32, [m00, m01, m10, m11]);
+float_primitive!(Float3x3, f32, [m00, m01, m02, m10, m11, m12, m20, m21, m22]);
+float_primitive!(
+    Float4x4,
+    f32,
+    [
+        m00, m01, m02, m03, m10, m11, m12, m13, m20, m21, m22, m23, m30, m31, m32, m33
+    ]
+);
+
+float_primitive!(Double2x2, f64, [m00, m01, m10, m11]);
+float_primitive!(
+    Double3x3,
+    f64,
+    [m00, m01, m02, m10, m11, m12, m20, m21, m22]
+);
+float_primitive!(
+    Double4x4,
+    f64,
+    [
+        m00, m01, m02, m03, m10, m11, m12, m13, m20, m21, m22, m23, m30, m31, m32, m33
+    ]
+);
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
 pub struct Color {
     #[serde(with = "super::floats::Ser")]
     pub r: f32,
@@ -75,7 +106,7 @@ pub struct Color {
     pub a: f32,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
 pub struct ColorX {
     #[serde(with = "super::floats::Ser")]
     pub r: f32,
@@ -88,7 +119,7 @@ pub struct ColorX {
     pub profile: String,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
 pub struct Color32 {
     pub r: u8,
     pub g: u8,
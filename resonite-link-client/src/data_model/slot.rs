@@ -2,7 +2,7 @@ use super::{Component, Field, ID, Worker, Reference};
 use crate::data_model::primitives::{Float3, FloatQ};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Slot {
     pub id: String,
@@ -37,7 +37,7 @@ impl Slot {
     }
 
     pub fn is_root_slot(&self) -> bool {
-        Some(Self::root_slot_id()) == self.name.value.as_deref().into()
+        Some(Self::root_slot_id()) == self.name.value.as_deref()
     }
 }
 
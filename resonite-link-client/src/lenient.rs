@@ -0,0 +1,534 @@
+use crate::data_model::{Component, Member, Slot};
+use crate::responses::{ParseDiagnostic, ParseError, Response, ResponseKind};
+use serde::de::{DeserializeOwned, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt::Formatter;
+
+/// Parses a response, replacing broken slots, components and members with placeholders.
+/// Only a broken envelope, such as a missing `success`, fails the whole response.
+pub(crate) fn parse_response(text: &str) -> serde_json::Result<Response> {
+    let mut value: Value = serde_json::from_str(text)?;
+    // The data is parsed piece by piece, so the envelope is parsed without it.
+    let data = value.get_mut("data").map(Value::take);
+    let mut response = Response::deserialize(&value)?;
+    let mut walk = Walk::default();
+    let location = Location {
+        path: "data".into(),
+        ..Default::default()
+    };
+    match (&mut response.kind, data) {
+        (_, None | Some(Value::Null)) => {}
+        (ResponseKind::SlotData { data, .. }, Some(value)) => {
+            *data = Some(walk.slot(value, location));
+        }
+        (ResponseKind::ComponentData { data }, Some(value)) => {
+            *data = Some(walk.component(value, location));
+        }
+        (ResponseKind::Response, Some(_)) => {}
+    }
+    response.diagnostics = walk.diagnostics;
+    Ok(response)
+}
+
+/// Parses a response strictly, one slot, component and member at a time in the order they were
+/// received, and returns where the first broken one is and the error it failed with.
+/// `None` when the response parses, or when the envelope itself is broken.
+pub(crate) fn first_broken(text: &str) -> Option<ParseError> {
+    let mut value: Ordered = serde_json::from_str(text).ok()?;
+    let data = value.take("data");
+    let response = Response::deserialize(Value::from(value)).ok()?;
+    let path = "data".to_owned();
+    let checked = match (response.kind, data) {
+        (_, None | Some(Ordered::Other(Value::Null))) => Ok(()),
+        (ResponseKind::SlotData { .. }, Some(value)) => check_slot(value, path),
+        (ResponseKind::ComponentData { .. }, Some(value)) => check_component(value, path),
+        (ResponseKind::Response, Some(_)) => Ok(()),
+    };
+    checked.err()
+}
+
+/// A slot is checked before the components and children nested in it.
+fn check_slot(mut value: Ordered, path: String) -> Result<(), ParseError> {
+    let nested = value.take_arrays(&["components", "children"]);
+    check::<Slot>(value, &path)?;
+    for (key, values) in nested {
+        for (i, value) in values.into_iter().enumerate() {
+            let path = format!("{}.{}[{}]", path, key, i);
+            match key.as_str() {
+                "components" => check_component(value, path)?,
+                _ => check_slot(value, path)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_component(mut value: Ordered, path: String) -> Result<(), ParseError> {
+    let members = value.take_object("members");
+    check::<Component>(value, &path)?;
+    check_members(members, &path)
+}
+
+fn check_member(mut value: Ordered, path: String) -> Result<(), ParseError> {
+    let (elements, members) = match value.type_name() {
+        Some("list") => (value.take_arrays(&["elements"]), Vec::new()),
+        Some("syncObject") => (Vec::new(), value.take_object("members")),
+        _ => (Vec::new(), Vec::new()),
+    };
+    check::<Member>(value, &path)?;
+    for (_, values) in elements {
+        for (i, value) in values.into_iter().enumerate() {
+            check_member(value, format!("{}.elements[{}]", path, i))?;
+        }
+    }
+    check_members(members, &path)
+}
+
+fn check_members(members: Vec<(String, Ordered)>, path: &str) -> Result<(), ParseError> {
+    members
+        .into_iter()
+        .try_for_each(|(name, member)| check_member(member, format!("{}.members.{}", path, name)))
+}
+
+/// Parses a value that has its nested values taken out already.
+fn check<T: DeserializeOwned>(value: Ordered, path: &str) -> Result<(), ParseError> {
+    let value = Value::from(value);
+    match T::deserialize(&value) {
+        Ok(_) => Ok(()),
+        Err(source) => Err(ParseError {
+            path: Some(path.to_owned()),
+            type_name: value
+                .get("$type")
+                .and_then(Value::as_str)
+                .map(str::to_owned),
+            fragment: Some(value),
+            source,
+        }),
+    }
+}
+
+/// A JSON value that keeps object keys in the order they were received, unlike [`Value`].
+enum Ordered {
+    Object(Vec<(String, Ordered)>),
+    Array(Vec<Ordered>),
+    Other(Value),
+}
+
+impl Ordered {
+    fn entries(&mut self) -> &mut [(String, Ordered)] {
+        match self {
+            Ordered::Object(entries) => entries,
+            _ => &mut [],
+        }
+    }
+
+    fn type_name(&self) -> Option<&str> {
+        let Ordered::Object(entries) = self else {
+            return None;
+        };
+        entries.iter().find_map(|(key, value)| match value {
+            Ordered::Other(Value::String(type_name)) if key == "$type" => Some(type_name.as_str()),
+            _ => None,
+        })
+    }
+
+    fn take(&mut self, key: &str) -> Option<Ordered> {
+        self.entries()
+            .iter_mut()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| std::mem::replace(value, Ordered::Other(Value::Null)))
+    }
+
+    /// Like [`take_array`], for each of `keys` in the order they were received.
+    fn take_arrays(&mut self, keys: &[&str]) -> Vec<(String, Vec<Ordered>)> {
+        self.entries()
+            .iter_mut()
+            .filter(|(key, _)| keys.contains(&key.as_str()))
+            .filter_map(|(key, value)| match value {
+                Ordered::Array(nested) => Some((key.clone(), std::mem::take(nested))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Like [`take_object`].
+    fn take_object(&mut self, key: &str) -> Vec<(String, Ordered)> {
+        self.entries()
+            .iter_mut()
+            .find_map(|(name, value)| match value {
+                Ordered::Object(nested) if name == key => Some(std::mem::take(nested)),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl From<Ordered> for Value {
+    fn from(value: Ordered) -> Self {
+        match value {
+            Ordered::Object(entries) => Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, value.into()))
+                    .collect(),
+            ),
+            Ordered::Array(values) => Value::Array(values.into_iter().map(Value::from).collect()),
+            Ordered::Other(value) => value,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Ordered {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(OrderedVisitor)
+    }
+}
+
+struct OrderedVisitor;
+
+impl<'de> Visitor<'de> for OrderedVisitor {
+    type Value = Ordered;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Ordered, E> {
+        Ok(Ordered::Other(v.into()))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Ordered, E> {
+        Ok(Ordered::Other(v.into()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Ordered, E> {
+        Ok(Ordered::Other(v.into()))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Ordered, E> {
+        Ok(Ordered::Other(v.into()))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Ordered, E> {
+        Ok(Ordered::Other(v.into()))
+    }
+
+    fn visit_unit<E>(self) -> Result<Ordered, E> {
+        Ok(Ordered::Other(Value::Null))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Ordered, A::Error> {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Ordered::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Ordered, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Ordered::Object(entries))
+    }
+}
+
+#[derive(Clone, Default)]
+struct Location {
+    path: String,
+    slot_id: Option<String>,
+    component_id: Option<String>,
+    member: Option<String>,
+}
+
+impl Location {
+    fn child(&self, path: impl std::fmt::Display) -> Self {
+        Self {
+            path: format!("{}{}", self.path, path),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Default)]
+struct Walk {
+    diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl Walk {
+    fn slot(&mut self, mut value: Value, mut location: Location) -> Slot {
+        location.slot_id = id_of(&value);
+        // Nested values are parsed on their own, so a broken child only replaces that child.
+        let components = take_array(&mut value, "components")
+            .into_iter()
+            .enumerate()
+            .map(|(i, component)| {
+                self.component(
+                    component,
+                    location.child(format_args!(".components[{}]", i)),
+                )
+            })
+            .collect();
+        let children = take_array(&mut value, "children")
+            .into_iter()
+            .enumerate()
+            .map(|(i, child)| self.slot(child, location.child(format_args!(".children[{}]", i))))
+            .collect();
+        let mut slot = Slot::deserialize(&value).unwrap_or_else(|e| {
+            self.report(&location, &value, e);
+            Slot {
+                id: location.slot_id.clone().unwrap_or_default(),
+                is_reference_only: true,
+                ..Default::default()
+            }
+        });
+        slot.components = components;
+        slot.children = children;
+        slot
+    }
+
+    fn component(&mut self, mut value: Value, mut location: Location) -> Component {
+        location.component_id = id_of(&value);
+        let members = take_object(&mut value, "members")
+            .into_iter()
+            .map(|(name, member)| {
+                let location = Location {
+                    member: Some(name.clone()),
+                    ..location.child(format_args!(".members.{}", name))
+                };
+                (name, self.member(member, location))
+            })
+            .collect();
+        let mut component = Component::deserialize(&value).unwrap_or_else(|e| {
+            self.report(&location, &value, e);
+            Component {
+                id: location.component_id.clone().unwrap_or_default(),
+                is_reference_only: true,
+                component_type: value
+                    .get("componentType")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_owned(),
+                members: HashMap::new(),
+            }
+        });
+        component.members = members;
+        component
+    }
+
+    fn member(&mut self, mut value: Value, location: Location) -> Member {
+        let (elements, members) = match value.get("$type").and_then(Value::as_str) {
+            Some("list") => (take_array(&mut value, "elements"), Map::new()),
+            Some("syncObject") => (Vec::new(), take_object(&mut value, "members")),
+            _ => (Vec::new(), Map::new()),
+        };
+        let elements: Vec<_> = elements
+            .into_iter()
+            .enumerate()
+            .map(|(i, element)| {
+                self.member(element, location.child(format_args!(".elements[{}]", i)))
+            })
+            .collect();
+        let members: HashMap<_, _> = members
+            .into_iter()
+            .map(|(name, member)| {
+                let member = self.member(member, location.child(format_args!(".members.{}", name)));
+                (name, member)
+            })
+            .collect();
+        match Member::deserialize(&value) {
+            Ok(Member::SyncList(mut list)) => {
+                list.elements = elements;
+                Member::SyncList(list)
+            }
+            Ok(Member::SyncObject(mut object)) => {
+                object.members = members;
+                Member::SyncObject(object)
+            }
+            Ok(member) => member,
+            Err(e) => {
+                self.report(&location, &value, e);
+                // Kept as received, so the member is sent back unchanged.
+                if let Value::Object(object) = &mut value {
+                    if !elements.is_empty() {
+                        object.insert("elements".into(), to_value(elements));
+                    }
+                    if !members.is_empty() {
+                        object.insert("members".into(), to_value(members));
+                    }
+                }
+                Member::Unknown {
+                    type_name: value
+                        .get("$type")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_owned(),
+                    id: id_of(&value).unwrap_or_default(),
+                    raw: value,
+                }
+            }
+        }
+    }
+
+    /// Records a broken value, which has its nested values taken out already.
+    fn report(&mut self, location: &Location, value: &Value, error: serde_json::Error) {
+        self.diagnostics.push(ParseDiagnostic {
+            path: location.path.clone(),
+            slot_id: location.slot_id.clone(),
+            component_id: location.component_id.clone(),
+            member: location.member.clone(),
+            type_name: value
+                .get("$type")
+                .and_then(Value::as_str)
+                .map(str::to_owned),
+            fragment: value.clone(),
+            error: error.to_string(),
+        });
+    }
+}
+
+fn id_of(value: &Value) -> Option<String> {
+    value.get("id").and_then(Value::as_str).map(str::to_owned)
+}
+
+/// Takes a nested array out, leaving an empty one so the parent can be parsed on its own.
+/// Anything but an array is left in place to fail the parent.
+fn take_array(value: &mut Value, key: &str) -> Vec<Value> {
+    match value.get_mut(key) {
+        Some(Value::Array(nested)) => std::mem::take(nested),
+        _ => Vec::new(),
+    }
+}
+
+/// Like [`take_array`], for a nested object.
+fn take_object(value: &mut Value, key: &str) -> Map<String, Value> {
+    match value.get_mut(key) {
+        Some(Value::Object(nested)) => std::mem::take(nested),
+        _ => Map::new(),
+    }
+}
+
+fn to_value(nested: impl serde::Serialize) -> Value {
+    serde_json::to_value(nested).expect("members always serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::responses::ResponseKind;
+    use serde_json::json;
+
+    fn slot(id: &str, components: Value, children: Value) -> Value {
+        json!({
+            "id": id,
+            "isReferenceOnly": false,
+            "parent": { "id": format!("{}_parent", id), "targetId": null, "targetType": "FrooxEngine.Slot" },
+            "name": { "id": format!("{}_name", id), "value": id },
+            "tag": { "id": format!("{}_tag", id), "value": null },
+            "position": { "id": format!("{}_pos", id), "value": { "x": 0, "y": 0, "z": 0 } },
+            "rotation": { "id": format!("{}_rot", id), "value": { "x": 0, "y": 0, "z": 0, "w": 1 } },
+            "scale": { "id": format!("{}_scale", id), "value": { "x": 1, "y": 1, "z": 1 } },
+            "isActive": { "id": format!("{}_active", id), "value": true },
+            "isPersistent": { "id": format!("{}_persistent", id), "value": true },
+            "orderOffset": { "id": format!("{}_order", id), "value": 0 },
+            "components": components,
+            "children": children,
+        })
+    }
+
+    fn slot_data(data: Value) -> String {
+        json!({
+            "$type": "slotData",
+            "depth": 1,
+            "data": data,
+            "sourceMessageId": "Taco",
+            "success": true,
+            "errorInfo": null,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn replaces_broken_members() {
+        let text = slot_data(slot(
+            "Root",
+            json!([{
+                "id": "Mesh",
+                "isReferenceOnly": false,
+                "componentType": "FrooxEngine.MeshRenderer",
+                "members": {
+                    "Enabled": { "$type": "bool", "id": "Enabled", "value": true },
+                    "Sorting": { "$type": "int", "id": "Sorting", "value": "Burrito" },
+                },
+            }]),
+            json!([]),
+        ));
+
+        let response = parse_response(&text).unwrap();
+
+        assert_eq!(
+            response.diagnostics,
+            vec![ParseDiagnostic {
+                path: "data.components[0].members.Sorting".into(),
+                slot_id: Some("Root".into()),
+                component_id: Some("Mesh".into()),
+                member: Some("Sorting".into()),
+                type_name: Some("int".into()),
+                fragment: json!({ "$type": "int", "id": "Sorting", "value": "Burrito" }),
+                error: response.diagnostics[0].error.clone(),
+            }]
+        );
+        let ResponseKind::SlotData {
+            data: Some(root), ..
+        } = response.kind
+        else {
+            panic!("expected slot data, got {:?}", response.kind);
+        };
+        let members = &root.components[0].members;
+        assert!(matches!(members["Enabled"], Member::Bool(_)));
+        assert!(matches!(
+            &members["Sorting"],
+            Member::Unknown { type_name, id, .. } if type_name == "int" && id == "Sorting"
+        ));
+    }
+
+    #[test]
+    fn replaces_broken_components_and_slots() {
+        let text = slot_data(slot(
+            "Root",
+            json!([{ "id": "Broken", "componentType": "FrooxEngine.Taco" }]),
+            json!([
+                { "id": "Child", "isReferenceOnly": false },
+                slot("Sibling", json!(null), json!(null)),
+            ]),
+        ));
+
+        let response = parse_response(&text).unwrap();
+
+        let paths: Vec<_> = response
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["data.components[0]", "data.children[0]"]);
+        assert_eq!(response.diagnostics[1].slot_id.as_deref(), Some("Child"));
+        let ResponseKind::SlotData {
+            data: Some(root), ..
+        } = response.kind
+        else {
+            panic!("expected slot data, got {:?}", response.kind);
+        };
+        assert_eq!(root.components[0].component_type, "FrooxEngine.Taco");
+        assert!(root.components[0].is_reference_only);
+        assert!(root.children[0].is_reference_only);
+        assert_eq!(root.children[1].id, "Sibling");
+    }
+
+    #[test]
+    fn broken_envelopes_still_fail() {
+        assert!(parse_response(r#"{"$type": "slotData", "depth": 0}"#).is_err());
+    }
+}
@@ -1,12 +1,27 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
 mod controller;
 pub mod data_model;
+mod lenient;
 mod messages;
+pub mod recording;
+pub mod requests;
 pub mod responses;
 mod serde_helpers;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 
 #[cfg(test)]
 mod test_utils;
 
-pub use controller::Client;
-pub use messages::Message;
+pub use controller::{
+    Backpressure, BoxTransport, Client, ClientBuilder, ClientConfig, ClientError, ConnectionEvent,
+    ConnectionLoss, Connector, DEFAULT_TIMEOUT, DisconnectReason, IdStrategy, MemoryTransport,
+    OutOfBandMessage, OutageBehavior, ParseMode, QueueDepth, RateLimit, RateLimitStats, RateLimits,
+    ReconnectPolicy, RemoteError, RemoteErrorKind, SequentialIds, SessionError, SessionHealth,
+    SessionManager, ShutdownHandle, Transport, UuidIds, WebSocketConnector,
+};
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingClient;
+pub use messages::{Message, MessageKind};
 pub use responses::Response;
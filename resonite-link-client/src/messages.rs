@@ -2,8 +2,9 @@ use serde::{Deserialize, Serialize};
 
 use super::data_model::Component;
 use super::data_model::Slot;
+use super::data_model::{ComponentPatch, SlotPatch};
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MessageWrapper {
     /// The kind of message to execute.
@@ -14,7 +15,7 @@ pub struct MessageWrapper {
     pub message_id: String,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase", tag = "$type")]
 pub enum Message {
     #[serde(rename_all = "camelCase")]
@@ -42,7 +43,7 @@ pub enum Message {
         /// Data of the slot to set/update.
         /// When updating Slot, the ID must be specified.
         /// Any fields that are null will be left as is.
-        data: Slot,
+        data: SlotPatch,
     },
     #[serde(rename_all = "camelCase")]
     RemoveSlot {
@@ -69,7 +70,7 @@ pub enum Message {
     UpdateComponent {
         /// The state of the component data. Any members that are not included will be left as is.
         /// When updating the component, the ID must be specified!
-        data: Component,
+        data: ComponentPatch,
     },
     #[serde(rename_all = "camelCase")]
     RemoveComponent {
@@ -78,6 +79,61 @@ pub enum Message {
     },
 }
 
+impl Message {
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            Message::GetSlot { .. } => MessageKind::GetSlot,
+            Message::AddSlot { .. } => MessageKind::AddSlot,
+            Message::UpdateSlot { .. } => MessageKind::UpdateSlot,
+            Message::RemoveSlot { .. } => MessageKind::RemoveSlot,
+            Message::GetComponent { .. } => MessageKind::GetComponent,
+            Message::AddComponent { .. } => MessageKind::AddComponent,
+            Message::UpdateComponent { .. } => MessageKind::UpdateComponent,
+            Message::RemoveComponent { .. } => MessageKind::RemoveComponent,
+        }
+    }
+}
+
+/// The kind of a [`Message`], without any of its data.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MessageKind {
+    GetSlot,
+    AddSlot,
+    UpdateSlot,
+    RemoveSlot,
+    GetComponent,
+    AddComponent,
+    UpdateComponent,
+    RemoveComponent,
+}
+
+impl MessageKind {
+    /// The `$type` the message is sent as.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            MessageKind::GetSlot => "getSlot",
+            MessageKind::AddSlot => "addSlot",
+            MessageKind::UpdateSlot => "updateSlot",
+            MessageKind::RemoveSlot => "removeSlot",
+            MessageKind::GetComponent => "getComponent",
+            MessageKind::AddComponent => "addComponent",
+            MessageKind::UpdateComponent => "updateComponent",
+            MessageKind::RemoveComponent => "removeComponent",
+        }
+    }
+
+    /// If the message changes the world, instead of only reading it.
+    pub fn is_mutation(&self) -> bool {
+        !matches!(self, MessageKind::GetSlot | MessageKind::GetComponent)
+    }
+}
+
+impl std::fmt::Display for MessageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.type_name())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,4 +160,14 @@ mod tests {
             }),
         );
     }
+
+    #[test]
+    fn kind_matches_type_name() {
+        let message = Message::RemoveComponent {
+            component_id: "Taco".into(),
+        };
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["$type"], message.kind().type_name());
+        assert!(message.kind().is_mutation());
+    }
 }
@@ -0,0 +1,574 @@
+//! Recording sessions to JSONL files, and replaying them as a fake server.
+//!
+//! Each line of a recording is a [`RecordedFrame`]. A recording of a real session can be replayed
+//! with [`Replay`] to reproduce it in a test without a running Resonite instance.
+
+use crate::controller::{Connector, Transport};
+use futures_util::{FutureExt, Sink, SinkExt, Stream, StreamExt};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::{Error, Message};
+
+/// One line of a recording.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedFrame {
+    /// Milliseconds since the recording started.
+    pub elapsed_ms: u64,
+    pub direction: Direction,
+    pub frame: Frame,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum Direction {
+    /// Sent by the client.
+    Outbound,
+    /// Received from the server.
+    Inbound,
+}
+
+/// The contents of a websocket frame, pings and pongs are not recorded.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Frame {
+    Text { text: String },
+    Binary { data: Vec<u8> },
+    Close { code: Option<u16>, reason: String },
+}
+
+impl Frame {
+    fn from_message(message: &Message) -> Option<Self> {
+        match message {
+            Message::Text(text) => Some(Frame::Text {
+                text: text.to_string(),
+            }),
+            Message::Binary(data) => Some(Frame::Binary {
+                data: data.to_vec(),
+            }),
+            Message::Close(frame) => Some(Frame::Close {
+                code: frame.as_ref().map(|frame| frame.code.into()),
+                reason: frame
+                    .as_ref()
+                    .map(|frame| frame.reason.to_string())
+                    .unwrap_or_default(),
+            }),
+            Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => None,
+        }
+    }
+
+    fn into_message(self) -> Message {
+        match self {
+            Frame::Text { text } => Message::Text(text.into()),
+            Frame::Binary { data } => Message::Binary(data.into()),
+            Frame::Close { code, reason } => Message::Close(code.map(|code| CloseFrame {
+                code: code.into(),
+                reason: reason.into(),
+            })),
+        }
+    }
+}
+
+enum RecorderCommand {
+    Record(RecordedFrame),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Writes every frame of the transports it wraps to a JSONL file.
+///
+/// Clones write to the same file, so one recorder can follow a client across reconnections.
+/// Frames are written on a blocking task, so recording never blocks the recorded transport.
+/// Failing to write is logged, and does not affect the recorded transport.
+#[derive(Clone)]
+pub struct Recorder {
+    commands: mpsc::UnboundedSender<RecorderCommand>,
+    started: Instant,
+}
+
+impl Recorder {
+    /// Starts writing to `writer` on a blocking task, so it must be called within a Tokio runtime.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        tokio::task::spawn_blocking(move || write_frames(writer, receiver));
+        Self {
+            commands,
+            started: Instant::now(),
+        }
+    }
+
+    /// Records to a new file, replacing it if it exists.
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Wraps a transport so every frame sent or received on it is recorded.
+    pub fn wrap<T: Transport>(&self, transport: T) -> RecordingTransport<T> {
+        RecordingTransport {
+            inner: transport,
+            recorder: self.clone(),
+        }
+    }
+
+    /// Wraps a connector so every transport it opens is recorded,
+    /// for use with [`Client::connect_with`](crate::Client::connect_with).
+    pub fn connector(&self, connector: impl Connector) -> impl Connector {
+        let recorder = self.clone();
+        move || {
+            let recorder = recorder.clone();
+            connector
+                .connect()
+                .map(move |transport| transport.map(|transport| recorder.wrap(transport)))
+        }
+    }
+
+    /// Waits until every frame recorded so far is written and flushed.
+    pub async fn flush(&self) {
+        let (done, flushed) = oneshot::channel();
+        if self.commands.send(RecorderCommand::Flush(done)).is_ok() {
+            let _ = flushed.await;
+        }
+    }
+
+    fn record(&self, direction: Direction, message: &Message) {
+        let Some(frame) = Frame::from_message(message) else {
+            return;
+        };
+        let line = RecordedFrame {
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            direction,
+            frame,
+        };
+        // Only fails if the writer task is gone, which the recorded transport does not care about.
+        let _ = self.commands.send(RecorderCommand::Record(line));
+    }
+}
+
+/// Writes frames until every [`Recorder`] is dropped, flushing whenever it runs out of frames to
+/// write so a recording survives a crash without flushing every frame.
+fn write_frames(mut writer: impl Write, mut commands: mpsc::UnboundedReceiver<RecorderCommand>) {
+    while let Some(mut command) = commands.blocking_recv() {
+        loop {
+            match command {
+                RecorderCommand::Record(line) => {
+                    let result = serde_json::to_writer(&mut writer, &line)
+                        .map_err(std::io::Error::from)
+                        .and_then(|_| writer.write_all(b"\n"));
+                    if let Err(e) = result {
+                        error!("failed to record a frame: {}", e);
+                    }
+                }
+                RecorderCommand::Flush(done) => {
+                    flush(&mut writer);
+                    let _ = done.send(());
+                }
+            }
+            match commands.try_recv() {
+                Ok(next) => command = next,
+                Err(_) => break,
+            }
+        }
+        flush(&mut writer);
+    }
+}
+
+fn flush(writer: &mut impl Write) {
+    if let Err(e) = writer.flush() {
+        error!("failed to flush the recording: {}", e);
+    }
+}
+
+/// A transport that records every frame, see [`Recorder::wrap`].
+pub struct RecordingTransport<T> {
+    inner: T,
+    recorder: Recorder,
+}
+
+impl<T: Transport> Stream for RecordingTransport<T> {
+    type Item = Result<Message, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let item = self.inner.poll_next_unpin(cx);
+        if let Poll::Ready(Some(Ok(message))) = &item {
+            self.recorder.record(Direction::Inbound, message);
+        }
+        item
+    }
+}
+
+impl<T: Transport> Sink<Message> for RecordingTransport<T> {
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.inner.poll_ready_unpin(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Message) -> Result<(), Error> {
+        self.recorder.record(Direction::Outbound, &item);
+        self.inner.start_send_unpin(item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.inner.poll_flush_unpin(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.inner.poll_close_unpin(cx)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("failed to read the recording {0}")]
+    Io(#[from] std::io::Error),
+    #[error("line {line} of the recording is invalid {error}")]
+    InvalidRecording {
+        line: usize,
+        error: serde_json::Error,
+    },
+    #[error("the transport failed {0}")]
+    Transport(#[from] Error),
+    #[error("the client disconnected while {expected} was expected")]
+    ClientDisconnected { expected: String },
+    #[error("expected the client to send {expected}, but it sent {received}")]
+    UnexpectedMessage { expected: String, received: String },
+}
+
+/// Plays back a recording as the server side of a transport.
+///
+/// Outbound frames in the recording are expected from the client in order, and must match
+/// apart from their `messageId`. Inbound frames are sent with their `sourceMessageId` rewritten
+/// to the id the client used for the matching request. Frames are sent without the recorded delays.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    frames: Vec<RecordedFrame>,
+}
+
+impl Replay {
+    pub fn new(frames: Vec<RecordedFrame>) -> Self {
+        Self { frames }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, ReplayError> {
+        let mut frames = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            frames.push(serde_json::from_str(&line).map_err(|error| {
+                ReplayError::InvalidRecording {
+                    line: index + 1,
+                    error,
+                }
+            })?);
+        }
+        Ok(Self { frames })
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// Serves the recording over a transport, such as one end of a
+    /// [`MemoryTransport`](crate::MemoryTransport) given to the client under test.
+    /// Completes once every frame was played back.
+    pub async fn serve(&self, mut transport: impl Transport) -> Result<(), ReplayError> {
+        // Recorded message ids to the ids the client is using now.
+        let mut ids = HashMap::<String, String>::new();
+
+        for recorded in &self.frames {
+            match recorded.direction {
+                Direction::Outbound => {
+                    let received = next_frame(&mut transport).await?.ok_or_else(|| {
+                        ReplayError::ClientDisconnected {
+                            expected: describe(&recorded.frame),
+                        }
+                    })?;
+                    match_outbound(&recorded.frame, &received, &mut ids)?;
+                }
+                Direction::Inbound => {
+                    let frame = remap_inbound(&recorded.frame, &ids);
+                    transport.send(frame.into_message()).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the next recordable frame, or `None` if the client disconnected.
+async fn next_frame(transport: &mut impl Transport) -> Result<Option<Frame>, ReplayError> {
+    while let Some(message) = transport.next().await {
+        if let Some(frame) = Frame::from_message(&message?) {
+            return Ok(Some(frame));
+        }
+    }
+    Ok(None)
+}
+
+fn describe(frame: &Frame) -> String {
+    match frame {
+        Frame::Text { text } => text.clone(),
+        frame => format!("{:?}", frame),
+    }
+}
+
+fn match_outbound(
+    expected: &Frame,
+    received: &Frame,
+    ids: &mut HashMap<String, String>,
+) -> Result<(), ReplayError> {
+    let mismatch = || ReplayError::UnexpectedMessage {
+        expected: describe(expected),
+        received: describe(received),
+    };
+    let (Frame::Text { text: expected }, Frame::Text { text: received }) = (expected, received)
+    else {
+        return if expected == received {
+            Ok(())
+        } else {
+            Err(mismatch())
+        };
+    };
+    let (Ok(mut expected), Ok(mut received)) = (
+        serde_json::from_str::<serde_json::Value>(expected),
+        serde_json::from_str::<serde_json::Value>(received),
+    ) else {
+        return if expected == received {
+            Ok(())
+        } else {
+            Err(mismatch())
+        };
+    };
+
+    let expected_id = take_string(&mut expected, "messageId");
+    let received_id = take_string(&mut received, "messageId");
+    if expected != received {
+        return Err(mismatch());
+    }
+    if let (Some(expected_id), Some(received_id)) = (expected_id, received_id) {
+        ids.insert(expected_id, received_id);
+    }
+    Ok(())
+}
+
+fn remap_inbound(frame: &Frame, ids: &HashMap<String, String>) -> Frame {
+    let Frame::Text { text } = frame else {
+        return frame.clone();
+    };
+    let Ok(mut value) = serde_json::from_str::<serde_json::Value>(text) else {
+        return frame.clone();
+    };
+    let Some(id) = value
+        .get("sourceMessageId")
+        .and_then(|id| ids.get(id.as_str()?))
+    else {
+        return frame.clone();
+    };
+    value["sourceMessageId"] = id.clone().into();
+    Frame::Text {
+        text: value.to_string(),
+    }
+}
+
+fn take_string(value: &mut serde_json::Value, key: &str) -> Option<String> {
+    match value.as_object_mut()?.remove(key)? {
+        serde_json::Value::String(s) => Some(s),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::MemoryTransport;
+    use crate::messages::MessageWrapper;
+    use crate::responses::{Response, ResponseKind};
+    use crate::{Client, ClientError};
+    use std::sync::{Arc, Mutex};
+
+    /// A writer that can be read back after the recorder is done with it.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Answers every request with a slot named after the request's id.
+    async fn serve_slot_names(mut ws: MemoryTransport) {
+        while let Some(Ok(msg)) = ws.next().await {
+            let msg: MessageWrapper = serde_json::from_str(msg.to_text().unwrap()).unwrap();
+            let response = Response {
+                source_message_id: Some(msg.message_id.clone()),
+                success: true,
+                error_info: None,
+                diagnostics: Vec::new(),
+                kind: ResponseKind::SlotData {
+                    depth: 0,
+                    data: Some(crate::data_model::Slot {
+                        id: msg.message_id,
+                        ..Default::default()
+                    }),
+                },
+            };
+            ws.send(Message::Text(
+                serde_json::to_string(&response).unwrap().into(),
+            ))
+            .await
+            .unwrap();
+        }
+    }
+
+    async fn record_root_slot_ids(buffer: &SharedBuffer) -> Vec<String> {
+        let recorder = Recorder::new(buffer.clone());
+        let (client_end, server_end) = MemoryTransport::pair();
+        tokio::spawn(serve_slot_names(server_end));
+        let client = Client::with_transport(recorder.wrap(client_end), Default::default());
+
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            ids.push(client.get_slot("Root", 0, false).await.unwrap().id);
+        }
+        client.close().await.unwrap();
+        recorder.flush().await;
+        ids
+    }
+
+    #[tokio::test]
+    async fn records_both_directions() {
+        let buffer = SharedBuffer::default();
+        record_root_slot_ids(&buffer).await;
+
+        let replay = Replay::from_reader(&buffer.0.lock().unwrap()[..]).unwrap();
+        let directions: Vec<_> = replay.frames().iter().map(|f| f.direction).collect();
+        assert_eq!(
+            directions,
+            vec![
+                Direction::Outbound,
+                Direction::Inbound,
+                Direction::Outbound,
+                Direction::Inbound,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn replays_with_new_message_ids() {
+        let buffer = SharedBuffer::default();
+        let recorded = record_root_slot_ids(&buffer).await;
+        let replay = Replay::from_reader(&buffer.0.lock().unwrap()[..]).unwrap();
+
+        let (client_end, server_end) = MemoryTransport::pair();
+        let served = tokio::spawn(async move { replay.serve(server_end).await });
+        let client = Client::with_transport(client_end, Default::default());
+
+        // The recorded ids are in the slot data, while the client pairs responses by its own ids.
+        for id in recorded {
+            assert_eq!(client.get_slot("Root", 0, false).await.unwrap().id, id);
+        }
+        client.close().await.unwrap();
+        served.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn replay_rejects_different_request() {
+        let buffer = SharedBuffer::default();
+        record_root_slot_ids(&buffer).await;
+        let replay = Replay::from_reader(&buffer.0.lock().unwrap()[..]).unwrap();
+
+        let (client_end, server_end) = MemoryTransport::pair();
+        let served = tokio::spawn(async move { replay.serve(server_end).await });
+        let client = Client::with_transport(client_end, Default::default());
+
+        let result = client
+            .send_with_timeout(
+                crate::Message::RemoveSlot {
+                    slot_id: "Root".into(),
+                },
+                Some(std::time::Duration::from_millis(50)),
+            )
+            .await;
+        assert!(matches!(result, Err(ClientError::ConnectionLost(_))));
+        assert!(matches!(
+            served.await.unwrap(),
+            Err(ReplayError::UnexpectedMessage { .. })
+        ));
+        client.close().await.unwrap();
+    }
+
+    /// A writer that blocks until the gate is opened.
+    struct GatedWriter {
+        gate: std::sync::mpsc::Receiver<()>,
+        buffer: SharedBuffer,
+    }
+
+    impl Write for GatedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let _ = self.gate.recv();
+            self.buffer.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_writers_do_not_block_the_transport() {
+        let buffer = SharedBuffer::default();
+        let (open, gate) = std::sync::mpsc::channel();
+        let recorder = Recorder::new(GatedWriter {
+            gate,
+            buffer: buffer.clone(),
+        });
+        let (client_end, server_end) = MemoryTransport::pair();
+        tokio::spawn(serve_slot_names(server_end));
+        let client = Client::with_transport(recorder.wrap(client_end), Default::default());
+
+        for _ in 0..2 {
+            tokio::time::timeout(
+                std::time::Duration::from_secs(1),
+                client.get_slot("Root", 0, false),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        }
+        client.close().await.unwrap();
+        drop(open);
+        recorder.flush().await;
+
+        let replay = Replay::from_reader(&buffer.0.lock().unwrap()[..]).unwrap();
+        assert_eq!(replay.frames().len(), 4);
+    }
+
+    #[test]
+    fn reports_invalid_lines() {
+        let result = Replay::from_reader("\n{ \"elapsedMs\": 0 }\n".as_bytes());
+        assert!(matches!(
+            result,
+            Err(ReplayError::InvalidRecording { line: 2, .. })
+        ));
+    }
+}
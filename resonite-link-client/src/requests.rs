@@ -0,0 +1,283 @@
+use crate::data_model::{Component, ComponentPatch, Slot, SlotPatch};
+use crate::messages::Message;
+use crate::responses::ResponseKind;
+
+/// A message with a known kind of response, see [`Client::request`](crate::Client::request).
+pub trait Request: Into<Message> {
+    /// What the response is converted into.
+    type Output;
+
+    /// A description of the expected response, used when a different kind is received.
+    const EXPECTED: &'static str;
+
+    /// Converts the response, giving the kind back if it is not the expected one.
+    fn from_response(kind: ResponseKind) -> Result<Self::Output, Box<ResponseKind>>;
+}
+
+/// Fetches a slot, see [`Message::GetSlot`].
+#[derive(PartialEq, Debug)]
+pub struct GetSlot {
+    pub slot_id: String,
+    pub depth: i32,
+    pub include_component_data: bool,
+}
+
+/// Adds a new slot, see [`Message::AddSlot`].
+#[derive(PartialEq, Debug)]
+pub struct AddSlot {
+    pub data: Slot,
+}
+
+/// Updates an existing slot, see [`Message::UpdateSlot`].
+#[derive(PartialEq, Debug)]
+pub struct UpdateSlot {
+    pub data: SlotPatch,
+}
+
+/// Removes a slot, see [`Message::RemoveSlot`].
+#[derive(PartialEq, Debug)]
+pub struct RemoveSlot {
+    pub slot_id: String,
+}
+
+/// Fetches a component, see [`Message::GetComponent`].
+#[derive(PartialEq, Debug)]
+pub struct GetComponent {
+    pub component_id: String,
+}
+
+/// Adds a component to a slot, see [`Message::AddComponent`].
+#[derive(PartialEq, Debug)]
+pub struct AddComponent {
+    pub container_slot_id: String,
+    pub data: Component,
+}
+
+/// Updates an existing component, see [`Message::UpdateComponent`].
+#[derive(PartialEq, Debug)]
+pub struct UpdateComponent {
+    pub data: ComponentPatch,
+}
+
+/// Removes a component, see [`Message::RemoveComponent`].
+#[derive(PartialEq, Debug)]
+pub struct RemoveComponent {
+    pub component_id: String,
+}
+
+fn slot_data(kind: ResponseKind) -> Result<Slot, Box<ResponseKind>> {
+    match kind {
+        ResponseKind::SlotData {
+            data: Some(slot), ..
+        } => Ok(slot),
+        kind => Err(Box::new(kind)),
+    }
+}
+
+fn component_data(kind: ResponseKind) -> Result<Component, Box<ResponseKind>> {
+    match kind {
+        ResponseKind::ComponentData {
+            data: Some(component),
+        } => Ok(component),
+        kind => Err(Box::new(kind)),
+    }
+}
+
+impl Request for GetSlot {
+    type Output = Slot;
+    const EXPECTED: &'static str = "slotData with a slot";
+
+    fn from_response(kind: ResponseKind) -> Result<Slot, Box<ResponseKind>> {
+        slot_data(kind)
+    }
+}
+
+impl Request for AddSlot {
+    type Output = Slot;
+    const EXPECTED: &'static str = "slotData with a slot";
+
+    fn from_response(kind: ResponseKind) -> Result<Slot, Box<ResponseKind>> {
+        slot_data(kind)
+    }
+}
+
+impl Request for UpdateSlot {
+    type Output = Slot;
+    const EXPECTED: &'static str = "slotData with a slot";
+
+    fn from_response(kind: ResponseKind) -> Result<Slot, Box<ResponseKind>> {
+        slot_data(kind)
+    }
+}
+
+impl Request for RemoveSlot {
+    type Output = ();
+    const EXPECTED: &'static str = "any response";
+
+    fn from_response(_: ResponseKind) -> Result<(), Box<ResponseKind>> {
+        Ok(())
+    }
+}
+
+impl Request for GetComponent {
+    type Output = Component;
+    const EXPECTED: &'static str = "componentData with a component";
+
+    fn from_response(kind: ResponseKind) -> Result<Component, Box<ResponseKind>> {
+        component_data(kind)
+    }
+}
+
+impl Request for AddComponent {
+    type Output = Component;
+    const EXPECTED: &'static str = "componentData with a component";
+
+    fn from_response(kind: ResponseKind) -> Result<Component, Box<ResponseKind>> {
+        component_data(kind)
+    }
+}
+
+impl Request for UpdateComponent {
+    type Output = Component;
+    const EXPECTED: &'static str = "componentData with a component";
+
+    fn from_response(kind: ResponseKind) -> Result<Component, Box<ResponseKind>> {
+        component_data(kind)
+    }
+}
+
+impl Request for RemoveComponent {
+    type Output = ();
+    const EXPECTED: &'static str = "any response";
+
+    fn from_response(_: ResponseKind) -> Result<(), Box<ResponseKind>> {
+        Ok(())
+    }
+}
+
+impl From<GetSlot> for Message {
+    fn from(request: GetSlot) -> Self {
+        Message::GetSlot {
+            slot_id: request.slot_id,
+            depth: request.depth,
+            include_component_data: request.include_component_data,
+        }
+    }
+}
+
+impl From<AddSlot> for Message {
+    fn from(request: AddSlot) -> Self {
+        Message::AddSlot { data: request.data }
+    }
+}
+
+impl From<UpdateSlot> for Message {
+    fn from(request: UpdateSlot) -> Self {
+        Message::UpdateSlot { data: request.data }
+    }
+}
+
+impl From<RemoveSlot> for Message {
+    fn from(request: RemoveSlot) -> Self {
+        Message::RemoveSlot {
+            slot_id: request.slot_id,
+        }
+    }
+}
+
+impl From<GetComponent> for Message {
+    fn from(request: GetComponent) -> Self {
+        Message::GetComponent {
+            component_id: request.component_id,
+        }
+    }
+}
+
+impl From<AddComponent> for Message {
+    fn from(request: AddComponent) -> Self {
+        Message::AddComponent {
+            data: request.data,
+            container_slot_id: request.container_slot_id,
+        }
+    }
+}
+
+impl From<UpdateComponent> for Message {
+    fn from(request: UpdateComponent) -> Self {
+        Message::UpdateComponent { data: request.data }
+    }
+}
+
+impl From<RemoveComponent> for Message {
+    fn from(request: RemoveComponent) -> Self {
+        Message::RemoveComponent {
+            component_id: request.component_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_slot_requires_slot_data() {
+        assert_eq!(
+            GetSlot::from_response(ResponseKind::SlotData {
+                depth: 0,
+                data: Some(Slot::default()),
+            }),
+            Ok(Slot::default())
+        );
+        assert_eq!(
+            GetSlot::from_response(ResponseKind::Response),
+            Err(Box::new(ResponseKind::Response))
+        );
+        assert_eq!(
+            GetSlot::from_response(ResponseKind::SlotData {
+                depth: 0,
+                data: None,
+            }),
+            Err(Box::new(ResponseKind::SlotData {
+                depth: 0,
+                data: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn get_component_rejects_slot_data() {
+        assert!(
+            GetComponent::from_response(ResponseKind::SlotData {
+                depth: 0,
+                data: Some(Slot::default()),
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn remove_accepts_any_response() {
+        assert_eq!(RemoveSlot::from_response(ResponseKind::Response), Ok(()));
+        assert_eq!(
+            RemoveComponent::from_response(ResponseKind::ComponentData { data: None }),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn converts_into_message() {
+        assert_eq!(
+            Message::from(GetSlot {
+                slot_id: "Root".into(),
+                depth: 2,
+                include_component_data: true,
+            }),
+            Message::GetSlot {
+                slot_id: "Root".into(),
+                depth: 2,
+                include_component_data: true,
+            }
+        );
+    }
+}
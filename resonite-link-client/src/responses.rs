@@ -1,7 +1,9 @@
 use crate::data_model::{Component, Slot};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     #[serde(flatten, default = "ResponseKind::Response")]
@@ -10,9 +12,92 @@ pub struct Response {
     pub source_message_id: Option<String>,
     pub success: bool,
     pub error_info: Option<String>,
+
+    /// What was replaced by placeholders when parsing leniently, see [`Response::from_str_lenient`].
+    #[serde(skip)]
+    pub diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl Response {
+    /// Parses a response, replacing slots, components and members that fail to parse with
+    /// placeholders instead of failing, and listing each of them in [`Response::diagnostics`].
+    ///
+    /// Broken members become [`Member::Unknown`](crate::data_model::Member::Unknown) holding
+    /// the JSON as received. Broken components and slots become reference only with their id,
+    /// keeping the members, components and children that did parse.
+    pub fn from_str_lenient(text: &str) -> serde_json::Result<Self> {
+        crate::lenient::parse_response(text)
+    }
+}
+
+/// Something that failed to parse, and was replaced by a placeholder.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseDiagnostic {
+    /// Where the value is in the response, such as `data.children[3].components[7].members.Material`.
+    pub path: String,
+    /// The closest slot containing the value.
+    pub slot_id: Option<String>,
+    /// The component containing the value, if any.
+    pub component_id: Option<String>,
+    /// The name of the component's member containing the value, if any.
+    pub member: Option<String>,
+    /// The `$type` of the value, if it has one.
+    pub type_name: Option<String>,
+    /// The value as received, without the slots, components and members nested in it.
+    pub fragment: Value,
+    pub error: String,
+}
+
+/// Why a response failed to parse, and where, see
+/// [`ClientError::MessageParsingError`](crate::ClientError::MessageParsingError).
+#[derive(Debug)]
+pub struct ParseError {
+    /// Where the broken value is, such as `data.children[3].components[7].members.Material`,
+    /// `None` when the response itself is broken.
+    pub path: Option<String>,
+    /// The `$type` of the broken value, if it has one.
+    pub type_name: Option<String>,
+    /// The broken value as received, without the slots, components and members nested in it.
+    pub fragment: Option<Value>,
+    pub source: serde_json::Error,
+}
+
+impl ParseError {
+    /// Finds which value in the response caused the error, by parsing it again strictly one
+    /// slot, component and member at a time, in the order they were received.
+    ///
+    /// The error becomes the one the first broken value failed with. `source` is kept, without a
+    /// path, when the response itself is broken.
+    pub fn locate(text: &str, source: serde_json::Error) -> Self {
+        crate::lenient::first_broken(text).unwrap_or(Self {
+            path: None,
+            type_name: None,
+            fragment: None,
+            source,
+        })
+    }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)?;
+        if let Some(path) = &self.path {
+            write!(f, " at {}", path)?;
+        }
+        if let Some(type_name) = &self.type_name {
+            write!(f, " ($type {})", type_name)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct FallbackResponse {
   pub source_message_id: Option<String>,
@@ -20,7 +105,8 @@ pub struct FallbackResponse {
   pub error_info: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase", tag = "$type")]
 pub enum ResponseKind {
     Response,
@@ -39,6 +125,106 @@ pub enum ResponseKind {
 mod tests {
     use super::*;
     use serde_json::json;
+
+    #[test]
+    fn locates_parse_errors() {
+        let text = json!({
+            "$type": "componentData",
+            "data": {
+                "id": "Taco",
+                "isReferenceOnly": false,
+                "componentType": "FrooxEngine.Taco",
+                "members": {
+                    "Fillings": {
+                        "$type": "list",
+                        "id": "Fillings",
+                        "elements": [
+                            { "$type": "string", "id": "Cheese", "value": "Cheddar" },
+                            { "$type": "float3", "id": "Salsa", "value": "Spicy" },
+                        ],
+                    },
+                },
+            },
+            "sourceMessageId": "Taco",
+            "success": true,
+            "errorInfo": null,
+        })
+        .to_string();
+        let source = serde_json::from_str::<Response>(&text).unwrap_err();
+
+        let error = ParseError::locate(&text, source);
+
+        assert_eq!(
+            error.path.as_deref(),
+            Some("data.members.Fillings.elements[1]")
+        );
+        assert_eq!(error.type_name.as_deref(), Some("float3"));
+        assert_eq!(error.fragment.unwrap()["id"], "Salsa");
+    }
+
+    #[test]
+    fn locates_the_value_that_failed_first() {
+        // The members are parsed in this order, but found broken in alphabetical order.
+        let text = r#"{
+            "$type": "componentData",
+            "data": {
+                "id": "Taco",
+                "isReferenceOnly": false,
+                "componentType": "FrooxEngine.Taco",
+                "members": {
+                    "Shell": { "$type": "bool", "id": "Shell", "value": "Crunchy" },
+                    "Filling": { "$type": "int", "id": "Filling", "value": "Beans" }
+                }
+            },
+            "sourceMessageId": "Taco",
+            "success": true,
+            "errorInfo": null
+        }"#;
+        let source = serde_json::from_str::<Response>(text).unwrap_err();
+
+        let error = ParseError::locate(text, source);
+
+        assert_eq!(error.path.as_deref(), Some("data.members.Shell"));
+        assert_eq!(error.type_name.as_deref(), Some("bool"));
+    }
+
+    #[test]
+    fn locates_the_first_of_the_same_errors() {
+        // Both members fail with the same message, and the lenient parse finds Shell second.
+        let text = r#"{
+            "$type": "componentData",
+            "data": {
+                "id": "Taco",
+                "isReferenceOnly": false,
+                "componentType": "FrooxEngine.Taco",
+                "members": {
+                    "Shell": { "$type": "float3", "id": "Shell", "value": "Spicy" },
+                    "Filling": { "$type": "float3", "id": "Filling", "value": "Spicy" }
+                }
+            },
+            "sourceMessageId": "Taco",
+            "success": true,
+            "errorInfo": null
+        }"#;
+        let source = serde_json::from_str::<Response>(text).unwrap_err();
+
+        let error = ParseError::locate(text, source);
+
+        assert_eq!(error.path.as_deref(), Some("data.members.Shell"));
+        assert_eq!(error.fragment.unwrap()["id"], "Shell");
+    }
+
+    #[test]
+    fn broken_envelopes_have_no_path() {
+        let text = r#"{"$type": "response", "success": "Taco"}"#;
+        let source = serde_json::from_str::<Response>(text).unwrap_err();
+
+        let error = ParseError::locate(text, source);
+
+        assert_eq!(error.path, None);
+        assert_eq!(error.to_string(), error.source.to_string());
+    }
+
     #[test]
     fn test_response() {
         // This is a GetData request from a new gridspace world.
@@ -0,0 +1,242 @@
+use crate::controller::{MemoryTransport, Transport};
+use crate::messages::{Message, MessageWrapper};
+use crate::responses::{Response, ResponseKind};
+use futures_util::future::BoxFuture;
+use futures_util::stream::FuturesUnordered;
+use futures_util::{FutureExt, SinkExt, StreamExt};
+use std::collections::VecDeque;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::{self, Message as WsMessage};
+
+/// A message the mock expects, and how it replies to it.
+#[derive(Clone, Debug)]
+pub struct Expectation {
+    message: Message,
+    reply: Reply,
+    delay: Option<Duration>,
+}
+
+#[derive(Clone, Debug)]
+enum Reply {
+    Respond(Box<ResponseKind>),
+    Fail(String),
+    Drop,
+}
+
+impl Expectation {
+    /// Expects the message and replies with a successful `response`.
+    pub fn message(message: Message) -> Self {
+        Self {
+            message,
+            reply: Reply::Respond(Box::new(ResponseKind::Response)),
+            delay: None,
+        }
+    }
+
+    /// Replies successfully with the response kind.
+    pub fn respond(mut self, kind: ResponseKind) -> Self {
+        self.reply = Reply::Respond(Box::new(kind));
+        self
+    }
+
+    /// Replies with `success: false` and the error info.
+    pub fn fail(mut self, error_info: impl Into<String>) -> Self {
+        self.reply = Reply::Fail(error_info.into());
+        self
+    }
+
+    /// Never replies.
+    pub fn drop_reply(mut self) -> Self {
+        self.reply = Reply::Drop;
+        self
+    }
+
+    /// Waits before replying, later messages are still handled while waiting
+    /// so their replies can overtake this one.
+    pub fn after(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum MockError {
+    #[error("message {index} was expected to be {expected:?}, but was {received:?}")]
+    UnexpectedMessage {
+        index: usize,
+        expected: Option<Box<Message>>,
+        received: Box<Message>,
+    },
+    #[error("the client sent a message that could not be parsed {0}")]
+    InvalidMessage(serde_json::Error),
+    #[error("the client disconnected before sending {remaining:?}")]
+    Unmet { remaining: Vec<Message> },
+    #[error("the transport failed {0}")]
+    Transport(#[from] tungstenite::Error),
+}
+
+/// A server that expects messages in a scripted order, see [`Expectation`].
+///
+/// A test usually [spawns](MockServer::spawn) the mock, gives the transport to
+/// [`Client::with_transport`](crate::Client::with_transport), and once the client is closed
+/// [verifies](MockHandle::verify) every expected message was sent.
+#[derive(Clone, Debug, Default)]
+pub struct MockServer {
+    expectations: VecDeque<Expectation>,
+}
+
+impl MockServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the next expected message.
+    pub fn expect(mut self, expectation: Expectation) -> Self {
+        self.expectations.push_back(expectation);
+        self
+    }
+
+    /// Serves the script on the other end of a memory transport.
+    pub fn spawn(self) -> (MemoryTransport, MockHandle) {
+        let (client_end, server_end) = MemoryTransport::pair();
+        let handle = tokio::spawn(self.serve(server_end));
+        (client_end, MockHandle { handle })
+    }
+
+    /// Serves the script until the client disconnects, failing on the first unexpected message
+    /// or if any expected message was never sent.
+    pub async fn serve(mut self, mut transport: impl Transport) -> Result<(), MockError> {
+        let mut replies = FuturesUnordered::<BoxFuture<'static, Option<Response>>>::new();
+        let mut index = 0;
+
+        loop {
+            tokio::select! {
+                Some(reply) = replies.next() => {
+                    if let Some(reply) = reply {
+                        let reply = serde_json::to_string(&reply).expect("responses always serialize");
+                        if transport.send(WsMessage::Text(reply.into())).await.is_err() {
+                            // The client disconnected before the reply was due.
+                            break;
+                        }
+                    }
+                }
+                frame = transport.next() => {
+                    let text = match frame.transpose()? {
+                        Some(WsMessage::Text(text)) => text,
+                        Some(_) => continue,
+                        None => break,
+                    };
+                    let received: MessageWrapper =
+                        serde_json::from_str(&text).map_err(MockError::InvalidMessage)?;
+
+                    let expectation = match self.expectations.pop_front() {
+                        Some(expectation) if expectation.message == received.inner => expectation,
+                        expected => {
+                            return Err(MockError::UnexpectedMessage {
+                                index,
+                                expected: expected.map(|e| Box::new(e.message)),
+                                received: Box::new(received.inner),
+                            });
+                        }
+                    };
+                    index += 1;
+                    replies.push(reply(expectation, received.message_id));
+                }
+            }
+        }
+
+        if self.expectations.is_empty() {
+            Ok(())
+        } else {
+            Err(MockError::Unmet {
+                remaining: self.expectations.into_iter().map(|e| e.message).collect(),
+            })
+        }
+    }
+}
+
+fn reply(expectation: Expectation, message_id: String) -> BoxFuture<'static, Option<Response>> {
+    async move {
+        if let Some(delay) = expectation.delay {
+            tokio::time::sleep(delay).await;
+        }
+        let (kind, success, error_info) = match expectation.reply {
+            Reply::Respond(kind) => (*kind, true, None),
+            Reply::Fail(error_info) => (ResponseKind::Response, false, Some(error_info)),
+            Reply::Drop => return None,
+        };
+        Some(Response {
+            kind,
+            source_message_id: Some(message_id),
+            success,
+            error_info,
+            diagnostics: Vec::new(),
+        })
+    }
+    .boxed()
+}
+
+/// A running [`MockServer::spawn`].
+pub struct MockHandle {
+    handle: JoinHandle<Result<(), MockError>>,
+}
+
+impl MockHandle {
+    /// Waits for the client to disconnect, and reports if the script was not followed.
+    pub async fn verify(self) -> Result<(), MockError> {
+        self.handle.await.expect("the mock server panicked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remove(slot_id: &str) -> Message {
+        Message::RemoveSlot {
+            slot_id: slot_id.into(),
+        }
+    }
+
+    async fn send_text(transport: &mut MemoryTransport, message_id: &str, message: Message) {
+        let text = serde_json::to_string(&MessageWrapper {
+            inner: message,
+            message_id: message_id.into(),
+        })
+        .unwrap();
+        transport.send(WsMessage::Text(text.into())).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reports_unmet_expectations() {
+        let (transport, mock) = MockServer::new()
+            .expect(Expectation::message(remove("Taco")))
+            .spawn();
+        drop(transport);
+
+        assert!(matches!(
+            mock.verify().await,
+            Err(MockError::Unmet { remaining }) if remaining == vec![remove("Taco")]
+        ));
+    }
+
+    #[tokio::test]
+    async fn reports_unexpected_messages() {
+        let (mut transport, mock) = MockServer::new()
+            .expect(Expectation::message(remove("Taco")))
+            .spawn();
+        send_text(&mut transport, "1", remove("Taco")).await;
+        send_text(&mut transport, "2", remove("Burrito")).await;
+
+        assert!(matches!(
+            mock.verify().await,
+            Err(MockError::UnexpectedMessage {
+                index: 1,
+                expected: None,
+                ..
+            })
+        ));
+    }
+}
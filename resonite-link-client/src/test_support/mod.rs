@@ -0,0 +1,11 @@
+//! Fake servers for testing code that uses the client without a running Resonite instance.
+//!
+//! Only available in this crate's tests, or with the `test-support` feature.
+
+mod mock;
+mod simulator;
+mod world;
+
+pub use mock::{Expectation, MockError, MockHandle, MockServer};
+pub use simulator::{Simulator, SimulatorServer};
+pub use world::World;
@@ -0,0 +1,241 @@
+use crate::controller::Transport;
+use crate::messages::{Message, MessageWrapper};
+use crate::responses::{Response, ResponseKind};
+use crate::test_support::World;
+use futures_util::{SinkExt, StreamExt};
+use log::warn;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_tungstenite::tungstenite::{self, Message as WsMessage};
+
+type MutationHook = Arc<dyn Fn(&World) + Send + Sync>;
+
+/// Serves a [`World`] over the Resonite Link protocol.
+///
+/// Clones share the same world, so a test can inspect or change the world while clients use it.
+#[derive(Clone, Default)]
+pub struct Simulator {
+    world: Arc<Mutex<World>>,
+    on_mutation: Option<MutationHook>,
+}
+
+impl Simulator {
+    pub fn new(world: World) -> Self {
+        Self {
+            world: Arc::new(Mutex::new(world)),
+            on_mutation: None,
+        }
+    }
+
+    /// Calls the hook with the changed world after every successful mutation,
+    /// while the world is still locked so the calls are in the order the mutations happened.
+    pub fn with_mutation_hook(mut self, hook: impl Fn(&World) + Send + Sync + 'static) -> Self {
+        self.on_mutation = Some(Arc::new(hook));
+        self
+    }
+
+    /// Locks the world, requests wait until the guard is dropped.
+    pub fn world(&self) -> MutexGuard<'_, World> {
+        self.world.lock().unwrap()
+    }
+
+    /// Applies a message to the world, the response has no `sourceMessageId`.
+    pub fn handle(&self, message: Message) -> Response {
+        let is_mutation = message.kind().is_mutation();
+        let mut world = self.world();
+        match world.handle(message) {
+            Ok(kind) => {
+                if let (true, Some(hook)) = (is_mutation, &self.on_mutation) {
+                    hook(&world);
+                }
+                Response {
+                    kind,
+                    source_message_id: None,
+                    success: true,
+                    error_info: None,
+                    diagnostics: Vec::new(),
+                }
+            }
+            Err(error_info) => failure(None, error_info),
+        }
+    }
+
+    /// Serves a single connection until the client disconnects.
+    pub async fn serve(&self, mut transport: impl Transport) -> Result<(), tungstenite::Error> {
+        while let Some(frame) = transport.next().await {
+            let text = match frame? {
+                WsMessage::Text(text) => text.to_string(),
+                WsMessage::Binary(data) => String::from_utf8_lossy(&data).into_owned(),
+                WsMessage::Close(_) => break,
+                WsMessage::Ping(_) | WsMessage::Pong(_) | WsMessage::Frame(_) => continue,
+            };
+
+            let response = match serde_json::from_str::<MessageWrapper>(&text) {
+                Ok(message) => Response {
+                    source_message_id: Some(message.message_id),
+                    ..self.handle(message.inner)
+                },
+                Err(e) => {
+                    warn!("the simulator received an invalid message: {}", e);
+                    let message_id = serde_json::from_str::<serde_json::Value>(&text)
+                        .ok()
+                        .and_then(|value| Some(value.get("messageId")?.as_str()?.to_owned()));
+                    failure(message_id, format!("Failed to parse the message: {}", e))
+                }
+            };
+            let response = serde_json::to_string(&response).expect("responses always serialize");
+            transport.send(WsMessage::Text(response.into())).await?;
+        }
+        Ok(())
+    }
+
+    /// Accepts websocket connections in the background, serving each of them until the
+    /// returned server is dropped.
+    pub async fn listen(&self, address: impl ToSocketAddrs) -> std::io::Result<SimulatorServer> {
+        let listener = TcpListener::bind(address).await?;
+        let local_addr = listener.local_addr()?;
+        let simulator = self.clone();
+        let handle = tokio::spawn(async move {
+            // Dropping the set when the server is aborted also stops every connection.
+            let mut connections = JoinSet::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                let simulator = simulator.clone();
+                connections.spawn(async move {
+                    let result = match tokio_tungstenite::accept_async(stream).await {
+                        Ok(ws) => simulator.serve(ws).await,
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = result {
+                        warn!("a simulator connection failed: {}", e);
+                    }
+                });
+            }
+        });
+        Ok(SimulatorServer { local_addr, handle })
+    }
+}
+
+fn failure(source_message_id: Option<String>, error_info: String) -> Response {
+    Response {
+        kind: ResponseKind::Response,
+        source_message_id,
+        success: false,
+        error_info: Some(error_info),
+        diagnostics: Vec::new(),
+    }
+}
+
+/// A running [`Simulator::listen`], which stops when dropped.
+pub struct SimulatorServer {
+    local_addr: SocketAddr,
+    handle: JoinHandle<()>,
+}
+
+impl SimulatorServer {
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The `ws://` address to give [`Client::connect`](crate::Client::connect).
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.local_addr)
+    }
+}
+
+impl Drop for SimulatorServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_model::{Component, Field, ID, Member, Slot};
+    use crate::{Client, ClientError, RemoteErrorKind};
+
+    async fn connect() -> (Simulator, SimulatorServer, Client) {
+        let simulator = Simulator::default();
+        let server = simulator.listen("127.0.0.1:0").await.unwrap();
+        let client = Client::connect(&server.url(), None).await.unwrap();
+        (simulator, server, client)
+    }
+
+    #[tokio::test]
+    async fn edits_slots_over_websocket() {
+        let (simulator, _server, client) = connect().await;
+
+        let added = client
+            .add_slot(Slot {
+                name: Field::new("", Some("Taco".into())),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(added.parent.target_id.as_deref(), Some("Root"));
+
+        let root = client.get_slot("Root", 1, false).await.unwrap();
+        assert_eq!(root.children, vec![added.clone()]);
+
+        client.remove_slot(added.id.clone()).await.unwrap();
+        assert!(simulator.world().find_slot(&added.id).is_none());
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn edits_components_over_websocket() {
+        let (_simulator, _server, client) = connect().await;
+
+        let added = client
+            .add_component(
+                "Root",
+                Component {
+                    id: String::new(),
+                    is_reference_only: false,
+                    component_type: "FrooxEngine.Grabbable".into(),
+                    members: [("Scalable".to_owned(), Member::Bool(Field::new("", false)))].into(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let mut update = added.clone();
+        update.members = [("Scalable".to_owned(), Member::Bool(Field::new("", true)))].into();
+        client.update_component(update).await.unwrap();
+        let fetched = client.get_component(added.id.clone()).await.unwrap();
+        let member_id = added.members["Scalable"].id();
+        assert!(!member_id.is_empty());
+        assert_eq!(
+            fetched.members["Scalable"],
+            Member::Bool(Field::new(member_id, true))
+        );
+
+        client.remove_component(added.id.clone()).await.unwrap();
+        let missing = client.get_component(added.id).await;
+        assert!(matches!(
+            missing,
+            Err(ClientError::Remote(error)) if error.kind == RemoteErrorKind::NotFound
+        ));
+        client.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn answers_invalid_messages_with_failures() {
+        let simulator = Simulator::default();
+        let (mut client_end, server_end) = crate::MemoryTransport::pair();
+        tokio::spawn(async move { simulator.serve(server_end).await });
+
+        client_end
+            .send(WsMessage::Text(
+                "{ \"$type\": \"taco\", \"messageId\": \"Taco\" }".into(),
+            ))
+            .await
+            .unwrap();
+        let response = client_end.next().await.unwrap().unwrap();
+        let response: Response = serde_json::from_str(response.to_text().unwrap()).unwrap();
+        assert_eq!(response.source_message_id.as_deref(), Some("Taco"));
+        assert!(!response.success);
+    }
+}
@@ -0,0 +1,823 @@
+use crate::data_model::{
+    Component, ComponentPatch, Field, FloatQ, Member, Reference, Slot, SlotPatch,
+};
+use crate::messages::Message;
+use crate::responses::ResponseKind;
+use serde_json::Value;
+
+const SLOT_TYPE: &str = "FrooxEngine.Slot";
+
+/// An in memory world that answers messages the way Resonite does.
+///
+/// Ids the world allocates start with `Sim_`, and continue after any such ids already in the world,
+/// so they never collide with ids of a loaded snapshot.
+#[derive(Clone, PartialEq, Debug)]
+pub struct World {
+    root: Slot,
+    next_id: u64,
+}
+
+impl Default for World {
+    /// A world with only an empty root slot.
+    fn default() -> Self {
+        let mut world = World {
+            root: Slot::default(),
+            next_id: 0,
+        };
+        let mut root = Slot {
+            name: Field::new("", Some(Slot::root_slot_id().to_owned())),
+            ..new_slot()
+        };
+        world.assign_slot_ids(&mut root, Slot::root_slot_id().to_owned(), None);
+        world.root = root;
+        world
+    }
+}
+
+/// A slot with the values Resonite gives a new slot.
+fn new_slot() -> Slot {
+    Slot {
+        rotation: Field::new(
+            "",
+            FloatQ {
+                w: 1.0,
+                ..Default::default()
+            },
+        ),
+        scale: Field::new(
+            "",
+            crate::data_model::Float3 {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
+            },
+        ),
+        is_active: Field::new("", true),
+        is_persistent: Field::new("", true),
+        ..Default::default()
+    }
+}
+
+impl World {
+    /// Creates a world from a snapshot of its root slot, such as a fully fetched `getSlot` of `Root`.
+    pub fn new(root: Slot) -> Self {
+        let next_id = serde_json::to_value(&root)
+            .map(|value| highest_allocated_id(&value))
+            .unwrap_or_default();
+        Self { root, next_id }
+    }
+
+    pub fn root(&self) -> &Slot {
+        &self.root
+    }
+
+    pub fn find_slot(&self, slot_id: &str) -> Option<&Slot> {
+        find_slot(&self.root, slot_id)
+    }
+
+    pub fn find_component(&self, component_id: &str) -> Option<&Component> {
+        find_component(&self.root, component_id)
+    }
+
+    /// Applies a message to the world, giving the response kind or the error info of a failure.
+    pub fn handle(&mut self, message: Message) -> Result<ResponseKind, String> {
+        match message {
+            Message::GetSlot {
+                slot_id,
+                depth,
+                include_component_data,
+            } => {
+                let slot = self
+                    .find_slot(&slot_id)
+                    .ok_or_else(|| slot_not_found(&slot_id))?;
+                Ok(ResponseKind::SlotData {
+                    depth,
+                    data: Some(render_slot(slot, depth, include_component_data)),
+                })
+            }
+            Message::AddSlot { data } => self.add_slot(data),
+            Message::UpdateSlot { data } => self.update_slot(data),
+            Message::RemoveSlot { slot_id } => {
+                if slot_id == self.root.id {
+                    return Err("The root slot cannot be removed".into());
+                }
+                remove_slot(&mut self.root, &slot_id).ok_or_else(|| slot_not_found(&slot_id))?;
+                Ok(ResponseKind::Response)
+            }
+            Message::GetComponent { component_id } => {
+                let component = self
+                    .find_component(&component_id)
+                    .ok_or_else(|| component_not_found(&component_id))?;
+                Ok(ResponseKind::ComponentData {
+                    data: Some(component.clone()),
+                })
+            }
+            Message::AddComponent {
+                data,
+                container_slot_id,
+            } => self.add_component(data, &container_slot_id),
+            Message::UpdateComponent { data } => self.update_component(data),
+            Message::RemoveComponent { component_id } => {
+                remove_component(&mut self.root, &component_id)
+                    .ok_or_else(|| component_not_found(&component_id))?;
+                Ok(ResponseKind::Response)
+            }
+        }
+    }
+
+    fn add_slot(&mut self, mut slot: Slot) -> Result<ResponseKind, String> {
+        let parent_id = slot
+            .parent
+            .target_id
+            .clone()
+            .unwrap_or_else(|| self.root.id.clone());
+        if self.find_slot(&parent_id).is_none() {
+            return Err(slot_not_found(&parent_id));
+        }
+
+        // Only the slot itself is added, components are added with their own messages.
+        slot.children.clear();
+        slot.components.clear();
+        slot.is_reference_only = false;
+        let id = self.allocate_id();
+        self.assign_slot_ids(&mut slot, id, Some(parent_id.clone()));
+        let rendered = render_slot(&slot, 0, false);
+        find_slot_mut(&mut self.root, &parent_id)
+            .expect("the parent was found above")
+            .children
+            .push(slot);
+        Ok(ResponseKind::SlotData {
+            depth: 0,
+            data: Some(rendered),
+        })
+    }
+
+    fn update_slot(&mut self, update: SlotPatch) -> Result<ResponseKind, String> {
+        let current_parent = find_parent(&self.root, &update.id)
+            .map(|parent| parent.id.clone())
+            .or_else(|| self.find_slot(&update.id).map(|_| String::new()))
+            .ok_or_else(|| slot_not_found(&update.id))?;
+
+        if let Some(new_parent) = update
+            .parent
+            .as_ref()
+            .and_then(|parent| parent.target_id.as_ref())
+            && *new_parent != current_parent
+            && update.id != self.root.id
+        {
+            let slot = self
+                .find_slot(&update.id)
+                .expect("the slot was found above");
+            if find_slot(slot, new_parent).is_some() {
+                return Err("A slot cannot be parented under itself".into());
+            }
+            if self.find_slot(new_parent).is_none() {
+                return Err(slot_not_found(new_parent));
+            }
+            let mut slot =
+                remove_slot(&mut self.root, &update.id).expect("the slot was found above");
+            slot.parent.target_id = Some(new_parent.clone());
+            find_slot_mut(&mut self.root, new_parent)
+                .expect("the parent was found above")
+                .children
+                .push(slot);
+        }
+
+        let slot = find_slot_mut(&mut self.root, &update.id).expect("the slot was found above");
+        // Fields that are left out, and null names and tags, are left as is.
+        if let Some(name) = update.name.and_then(|name| name.value) {
+            slot.name.value = Some(name);
+        }
+        if let Some(tag) = update.tag.and_then(|tag| tag.value) {
+            slot.tag.value = Some(tag);
+        }
+        if let Some(position) = update.position {
+            slot.position.value = position.value;
+        }
+        if let Some(rotation) = update.rotation {
+            slot.rotation.value = rotation.value;
+        }
+        if let Some(scale) = update.scale {
+            slot.scale.value = scale.value;
+        }
+        if let Some(is_active) = update.is_active {
+            slot.is_active.value = is_active.value;
+        }
+        if let Some(is_persistent) = update.is_persistent {
+            slot.is_persistent.value = is_persistent.value;
+        }
+        if let Some(order_offset) = update.order_offset {
+            slot.order_offset.value = order_offset.value;
+        }
+
+        Ok(ResponseKind::SlotData {
+            depth: 0,
+            data: Some(render_slot(slot, 0, false)),
+        })
+    }
+
+    fn add_component(
+        &mut self,
+        mut component: Component,
+        container_slot_id: &str,
+    ) -> Result<ResponseKind, String> {
+        if component.component_type.is_empty() {
+            return Err("Invalid component type".into());
+        }
+        if self.find_slot(container_slot_id).is_none() {
+            return Err(slot_not_found(container_slot_id));
+        }
+
+        component.id = self.allocate_id();
+        component.is_reference_only = false;
+        for member in component.members.values_mut() {
+            let mut value = to_json(member);
+            self.assign_member_ids(&mut value);
+            *member = from_json(value);
+        }
+        let slot =
+            find_slot_mut(&mut self.root, container_slot_id).expect("the slot was found above");
+        slot.components.push(component.clone());
+        Ok(ResponseKind::ComponentData {
+            data: Some(component),
+        })
+    }
+
+    fn update_component(&mut self, update: ComponentPatch) -> Result<ResponseKind, String> {
+        let component = self
+            .find_component(&update.id)
+            .ok_or_else(|| component_not_found(&update.id))?;
+        if let Some(component_type) = &update.component_type
+            && *component_type != component.component_type
+        {
+            return Err(format!(
+                "Component {} is of type {}, not {}",
+                update.id, component.component_type, component_type
+            ));
+        }
+        // Every member is checked before any is changed, so a failed update changes nothing.
+        let mut members = component.members.clone();
+        for (name, member_update) in update.members {
+            let member = members
+                .get_mut(&name)
+                .ok_or_else(|| format!("Member {} not found on component {}", name, update.id))?;
+            let mut value = to_json(member);
+            self.update_member(&mut value, to_json(&member_update), &name)?;
+            *member = from_json(value);
+        }
+
+        let component =
+            find_component_mut(&mut self.root, &update.id).expect("the component was found above");
+        // Members that are not included are left as is.
+        component.members = members;
+        Ok(ResponseKind::ComponentData {
+            data: Some(component.clone()),
+        })
+    }
+
+    /// Changes the values of a member, keeping its id and the ids of the members nested in it.
+    fn update_member(
+        &mut self,
+        member: &mut Value,
+        update: Value,
+        name: &str,
+    ) -> Result<(), String> {
+        let (Value::Object(member), Value::Object(update)) = (member, update) else {
+            return Err(format!("Invalid value for member {}", name));
+        };
+        let type_name = member
+            .get("$type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let update_type_name = update
+            .get("$type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if type_name != update_type_name {
+            return Err(format!(
+                "Member {} is of type {}, not {}",
+                name, type_name, update_type_name
+            ));
+        }
+
+        for (key, value) in update {
+            match (key.as_str(), member.get_mut(&key), value) {
+                ("$type" | "id", _, _) => {}
+                ("members", Some(Value::Object(members)), Value::Object(updates)) => {
+                    for (nested_name, nested_update) in updates {
+                        let nested_member = members.get_mut(&nested_name).ok_or_else(|| {
+                            format!("Member {} not found on {}", nested_name, name)
+                        })?;
+                        self.update_member(nested_member, nested_update, &nested_name)?;
+                    }
+                }
+                ("elements", Some(Value::Array(elements)), Value::Array(updates)) => {
+                    // Elements are matched by index, added elements are new members.
+                    elements.truncate(updates.len());
+                    for (index, element_update) in updates.into_iter().enumerate() {
+                        match elements.get_mut(index) {
+                            Some(element) => self.update_member(
+                                element,
+                                element_update,
+                                &format!("{}[{}]", name, index),
+                            )?,
+                            None => {
+                                let mut element = element_update;
+                                self.assign_member_ids(&mut element);
+                                elements.push(element);
+                            }
+                        }
+                    }
+                }
+                (_, _, value) => _ = member.insert(key, value),
+            }
+        }
+        Ok(())
+    }
+
+    /// Gives a serialized member, and the members nested in it, new ids.
+    fn assign_member_ids(&mut self, member: &mut Value) {
+        let Value::Object(member) = member else {
+            return;
+        };
+        member.insert("id".into(), self.allocate_id().into());
+        if let Some(Value::Array(elements)) = member.get_mut("elements") {
+            for element in elements {
+                self.assign_member_ids(element);
+            }
+        }
+        if let Some(Value::Object(members)) = member.get_mut("members") {
+            for nested in members.values_mut() {
+                self.assign_member_ids(nested);
+            }
+        }
+    }
+
+    fn allocate_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("Sim_{:X}", self.next_id)
+    }
+
+    fn assign_slot_ids(&mut self, slot: &mut Slot, id: String, parent_id: Option<String>) {
+        slot.id = id;
+        slot.parent = Reference {
+            id: self.allocate_id(),
+            target_id: parent_id,
+            target_type: SLOT_TYPE.into(),
+        };
+        slot.position.id = self.allocate_id();
+        slot.rotation.id = self.allocate_id();
+        slot.scale.id = self.allocate_id();
+        slot.is_active.id = self.allocate_id();
+        slot.is_persistent.id = self.allocate_id();
+        slot.name.id = self.allocate_id();
+        slot.tag.id = self.allocate_id();
+        slot.order_offset.id = self.allocate_id();
+    }
+}
+
+/// Members are edited as JSON, which treats every member type the same.
+fn to_json(member: &Member) -> Value {
+    serde_json::to_value(member).expect("members always serialize")
+}
+
+fn from_json(member: Value) -> Member {
+    serde_json::from_value(member).expect("members only get values of their own type")
+}
+
+/// The highest `Sim_` id anywhere in the serialized world.
+fn highest_allocated_id(value: &serde_json::Value) -> u64 {
+    match value {
+        serde_json::Value::String(id) => id
+            .strip_prefix("Sim_")
+            .and_then(|id| u64::from_str_radix(id, 16).ok())
+            .unwrap_or_default(),
+        serde_json::Value::Array(values) => values
+            .iter()
+            .map(highest_allocated_id)
+            .max()
+            .unwrap_or_default(),
+        serde_json::Value::Object(values) => values
+            .values()
+            .map(highest_allocated_id)
+            .max()
+            .unwrap_or_default(),
+        _ => 0,
+    }
+}
+
+fn slot_not_found(slot_id: &str) -> String {
+    format!("Slot with ID {} not found", slot_id)
+}
+
+fn component_not_found(component_id: &str) -> String {
+    format!("Component with ID {} not found", component_id)
+}
+
+/// Copies a slot the way `getSlot` returns it, children beyond the depth are only references,
+/// as are components when their data is not included.
+fn render_slot(slot: &Slot, depth: i32, include_component_data: bool) -> Slot {
+    let components = slot
+        .components
+        .iter()
+        .map(|component| {
+            if include_component_data {
+                component.clone()
+            } else {
+                Component {
+                    id: component.id.clone(),
+                    is_reference_only: true,
+                    component_type: component.component_type.clone(),
+                    members: Default::default(),
+                }
+            }
+        })
+        .collect();
+    let children = slot
+        .children
+        .iter()
+        .map(|child| {
+            if depth == 0 {
+                Slot {
+                    is_reference_only: true,
+                    components: Vec::new(),
+                    children: Vec::new(),
+                    ..shallow_copy(child)
+                }
+            } else {
+                render_slot(child, depth.max(0) - 1, include_component_data)
+            }
+        })
+        .collect();
+
+    Slot {
+        is_reference_only: false,
+        components,
+        children,
+        ..shallow_copy(slot)
+    }
+}
+
+fn shallow_copy(slot: &Slot) -> Slot {
+    Slot {
+        id: slot.id.clone(),
+        is_reference_only: slot.is_reference_only,
+        parent: slot.parent.clone(),
+        name: slot.name.clone(),
+        tag: slot.tag.clone(),
+        position: slot.position.clone(),
+        rotation: slot.rotation.clone(),
+        scale: slot.scale.clone(),
+        is_active: slot.is_active.clone(),
+        is_persistent: slot.is_persistent.clone(),
+        order_offset: slot.order_offset.clone(),
+        components: Vec::new(),
+        children: Vec::new(),
+    }
+}
+
+fn find_slot<'a>(slot: &'a Slot, slot_id: &str) -> Option<&'a Slot> {
+    if slot.id == slot_id {
+        return Some(slot);
+    }
+    slot.children
+        .iter()
+        .find_map(|child| find_slot(child, slot_id))
+}
+
+fn find_slot_mut<'a>(slot: &'a mut Slot, slot_id: &str) -> Option<&'a mut Slot> {
+    if slot.id == slot_id {
+        return Some(slot);
+    }
+    slot.children
+        .iter_mut()
+        .find_map(|child| find_slot_mut(child, slot_id))
+}
+
+fn find_parent<'a>(slot: &'a Slot, slot_id: &str) -> Option<&'a Slot> {
+    if slot.children.iter().any(|child| child.id == slot_id) {
+        return Some(slot);
+    }
+    slot.children
+        .iter()
+        .find_map(|child| find_parent(child, slot_id))
+}
+
+fn remove_slot(slot: &mut Slot, slot_id: &str) -> Option<Slot> {
+    if let Some(index) = slot.children.iter().position(|child| child.id == slot_id) {
+        return Some(slot.children.remove(index));
+    }
+    slot.children
+        .iter_mut()
+        .find_map(|child| remove_slot(child, slot_id))
+}
+
+fn find_component<'a>(slot: &'a Slot, component_id: &str) -> Option<&'a Component> {
+    slot.components
+        .iter()
+        .find(|component| component.id == component_id)
+        .or_else(|| {
+            slot.children
+                .iter()
+                .find_map(|child| find_component(child, component_id))
+        })
+}
+
+fn find_component_mut<'a>(slot: &'a mut Slot, component_id: &str) -> Option<&'a mut Component> {
+    if let Some(index) = slot
+        .components
+        .iter()
+        .position(|component| component.id == component_id)
+    {
+        return Some(&mut slot.components[index]);
+    }
+    slot.children
+        .iter_mut()
+        .find_map(|child| find_component_mut(child, component_id))
+}
+
+fn remove_component(slot: &mut Slot, component_id: &str) -> Option<Component> {
+    if let Some(index) = slot
+        .components
+        .iter()
+        .position(|component| component.id == component_id)
+    {
+        return Some(slot.components.remove(index));
+    }
+    slot.children
+        .iter_mut()
+        .find_map(|child| remove_component(child, component_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_model::{ID, SyncList};
+
+    fn child_named(world: &mut World, parent_id: &str, name: &str) -> String {
+        let Ok(ResponseKind::SlotData {
+            data: Some(slot), ..
+        }) = world.handle(Message::AddSlot {
+            data: Slot {
+                parent: Reference::new("", parent_id, SLOT_TYPE),
+                name: Field::new("", Some(name.into())),
+                ..new_slot()
+            },
+        })
+        else {
+            panic!("adding a slot failed");
+        };
+        slot.id
+    }
+
+    #[test]
+    fn get_slot_respects_depth() {
+        let mut world = World::default();
+        let child = child_named(&mut world, "Root", "Child");
+        child_named(&mut world, &child, "Grandchild");
+
+        let Ok(ResponseKind::SlotData {
+            data: Some(root), ..
+        }) = world.handle(Message::GetSlot {
+            slot_id: "Root".into(),
+            depth: 0,
+            include_component_data: false,
+        })
+        else {
+            panic!("the root exists");
+        };
+        assert!(!root.is_reference_only);
+        assert!(root.children[0].is_reference_only);
+        assert!(root.children[0].children.is_empty());
+
+        let Ok(ResponseKind::SlotData {
+            data: Some(root), ..
+        }) = world.handle(Message::GetSlot {
+            slot_id: "Root".into(),
+            depth: -1,
+            include_component_data: false,
+        })
+        else {
+            panic!("the root exists");
+        };
+        let grandchild = &root.children[0].children[0];
+        assert!(!grandchild.is_reference_only);
+        assert_eq!(grandchild.name.value.as_deref(), Some("Grandchild"));
+        assert_eq!(grandchild.parent.target_id.as_deref(), Some(child.as_str()));
+    }
+
+    #[test]
+    fn get_slot_includes_component_data() {
+        let mut world = World::default();
+        world
+            .handle(Message::AddComponent {
+                data: Component {
+                    id: String::new(),
+                    is_reference_only: false,
+                    component_type: "FrooxEngine.Grabbable".into(),
+                    members: [(
+                        "Scalable".to_owned(),
+                        crate::data_model::Member::Bool(Field::new("Taco", true)),
+                    )]
+                    .into(),
+                },
+                container_slot_id: "Root".into(),
+            })
+            .unwrap();
+
+        for include_component_data in [false, true] {
+            let Ok(ResponseKind::SlotData {
+                data: Some(root), ..
+            }) = world.handle(Message::GetSlot {
+                slot_id: "Root".into(),
+                depth: 0,
+                include_component_data,
+            })
+            else {
+                panic!("the root exists");
+            };
+            assert_eq!(
+                root.components[0].is_reference_only,
+                !include_component_data
+            );
+            assert_eq!(
+                root.components[0].members.len(),
+                include_component_data as usize
+            );
+        }
+    }
+
+    #[test]
+    fn reparents_slots() {
+        let mut world = World::default();
+        let first = child_named(&mut world, "Root", "First");
+        let second = child_named(&mut world, "Root", "Second");
+
+        let update = SlotPatch::new(&second).parent(&first);
+        world.handle(Message::UpdateSlot { data: update }).unwrap();
+        assert_eq!(world.find_slot(&first).unwrap().children[0].id, second);
+
+        let update = SlotPatch::new(&first).parent(second);
+        assert!(world.handle(Message::UpdateSlot { data: update }).is_err());
+    }
+
+    #[test]
+    fn keeps_fields_left_out_of_updates() {
+        let mut world = World::default();
+        let taco = child_named(&mut world, "Root", "Taco");
+        let update = SlotPatch::new(&taco).order_offset(3).is_active(false);
+
+        world.handle(Message::UpdateSlot { data: update }).unwrap();
+
+        let slot = world.find_slot(&taco).unwrap();
+        assert_eq!(slot.order_offset.value, 3);
+        assert!(!slot.is_active.value);
+        assert_eq!(slot.name.value.as_deref(), Some("Taco"));
+        assert_eq!(slot.scale.value.x, 1.0);
+    }
+
+    fn add_grabbable(world: &mut World) -> Component {
+        let Ok(ResponseKind::ComponentData {
+            data: Some(component),
+        }) = world.handle(Message::AddComponent {
+            data: Component {
+                id: String::new(),
+                is_reference_only: false,
+                component_type: "FrooxEngine.Grabbable".into(),
+                members: [
+                    ("Scalable".to_owned(), Member::Bool(Field::new("", true))),
+                    (
+                        "Receivers".to_owned(),
+                        Member::SyncList(SyncList {
+                            id: String::new(),
+                            elements: vec![Member::Int(Field::new("", 1))],
+                        }),
+                    ),
+                ]
+                .into(),
+            },
+            container_slot_id: "Root".into(),
+        })
+        else {
+            panic!("adding a component failed");
+        };
+        component
+    }
+
+    #[test]
+    fn allocates_member_ids() {
+        let mut world = World::default();
+        let component = add_grabbable(&mut world);
+
+        assert!(component.members["Scalable"].id().starts_with("Sim_"));
+        let Member::SyncList(receivers) = &component.members["Receivers"] else {
+            panic!("expected a list, got {:?}", component.members["Receivers"]);
+        };
+        assert!(receivers.id.starts_with("Sim_"));
+        assert!(receivers.elements[0].id().starts_with("Sim_"));
+    }
+
+    #[test]
+    fn updates_only_member_values() {
+        let mut world = World::default();
+        let component = add_grabbable(&mut world);
+        let update = ComponentPatch::new(&component.id)
+            .member("Scalable", Member::Bool(Field::new("", false)))
+            .member(
+                "Receivers",
+                Member::SyncList(SyncList {
+                    id: String::new(),
+                    elements: vec![
+                        Member::Int(Field::new("", 2)),
+                        Member::Int(Field::new("", 3)),
+                    ],
+                }),
+            );
+
+        world
+            .handle(Message::UpdateComponent { data: update })
+            .unwrap();
+
+        let members = &world.find_component(&component.id).unwrap().members;
+        let scalable_id = component.members["Scalable"].id();
+        assert_eq!(
+            members["Scalable"],
+            Member::Bool(Field::new(scalable_id, false))
+        );
+        let Member::SyncList(receivers) = &members["Receivers"] else {
+            panic!("expected a list, got {:?}", members["Receivers"]);
+        };
+        let Member::SyncList(added) = &component.members["Receivers"] else {
+            unreachable!();
+        };
+        assert_eq!(receivers.id, added.id);
+        assert_eq!(
+            receivers.elements[0],
+            Member::Int(Field::new(added.elements[0].id(), 2))
+        );
+        assert!(receivers.elements[1].id().starts_with("Sim_"));
+    }
+
+    #[test]
+    fn rejects_updates_to_unknown_or_retyped_members() {
+        let mut world = World::default();
+        let component = add_grabbable(&mut world);
+
+        for update in [
+            ComponentPatch::new(&component.id).member("Taco", Member::Bool(Field::new("", false))),
+            ComponentPatch::new(&component.id).member("Scalable", Member::Int(Field::new("", 0))),
+        ] {
+            assert!(
+                world
+                    .handle(Message::UpdateComponent { data: update })
+                    .is_err()
+            );
+        }
+        assert_eq!(world.find_component(&component.id), Some(&component));
+    }
+
+    #[test]
+    fn rejects_updates_of_another_component_type() {
+        let mut world = World::default();
+        let component = add_grabbable(&mut world);
+        let mut update = ComponentPatch::from(component.clone());
+        update.component_type = Some("FrooxEngine.Taco".into());
+
+        assert_eq!(
+            world.handle(Message::UpdateComponent { data: update }),
+            Err(format!(
+                "Component {} is of type FrooxEngine.Grabbable, not FrooxEngine.Taco",
+                component.id
+            ))
+        );
+    }
+
+    #[test]
+    fn cannot_remove_root() {
+        let mut world = World::default();
+        assert!(
+            world
+                .handle(Message::RemoveSlot {
+                    slot_id: "Root".into()
+                })
+                .is_err()
+        );
+        assert_eq!(
+            world.handle(Message::RemoveSlot {
+                slot_id: "Taco".into()
+            }),
+            Err("Slot with ID Taco not found".into())
+        );
+    }
+
+    #[test]
+    fn loaded_world_continues_ids() {
+        let mut world = World::default();
+        let first = child_named(&mut world, "Root", "First");
+
+        let mut world = World::new(world.root().clone());
+        let second = child_named(&mut world, "Root", "Second");
+        assert_ne!(first, second);
+        assert!(world.find_slot(&first).is_some());
+    }
+}
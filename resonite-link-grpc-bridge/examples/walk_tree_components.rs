@@ -25,7 +25,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut slots = Vec::new();
 
-    gather_children(&tree.data.as_ref().unwrap(), &mut slots);
+    gather_children(tree.data.as_ref().unwrap(), &mut slots);
 
     for slot in slots {
         println!(
@@ -47,7 +47,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn gather_children<'a>(slot: &'a Slot, slots: &mut Vec<&'a Slot>) {
-    slots.push(&slot);
+    slots.push(slot);
     for child in &slot.children {
         gather_children(child, slots);
     }
@@ -1,6 +1,8 @@
 use crate::server::LinkProxy;
 use clap::Parser;
-use log::info;
+use log::{info, warn};
+use resonite_link_client::ConnectionEvent;
+use tokio::sync::broadcast::error::RecvError;
 use tonic::transport::Server;
 
 mod pb;
@@ -31,6 +33,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("ResoniteLink connected, starting GRPC server.");
 
+    let mut events = client.subscribe_events();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(ConnectionEvent::Connected) => info!("ResoniteLink reconnected."),
+                Ok(ConnectionEvent::Reconnecting { attempt, delay }) => {
+                    info!("Reconnecting to ResoniteLink in {:?} (attempt {}).", delay, attempt)
+                }
+                Ok(event) => warn!("ResoniteLink link health: {:?}", event),
+                Err(RecvError::Lagged(skipped)) => warn!("Skipped {} link events.", skipped),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
     Server::builder()
         .add_service(pb::resonite_link_server::ResoniteLinkServer::new(
             LinkProxy::new(client),
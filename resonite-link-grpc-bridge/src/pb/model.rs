@@ -1,7 +1,7 @@
 use super::convert_utils::*;
 use super::*;
 use ::resonite_link_client::data_model;
-use ::resonite_link_client::data_model::{F32, F64};
+use ::resonite_link_client::data_model::{F32, F64, ID};
 use std::convert::Infallible;
 use std::num::TryFromIntError;
 use thiserror::Error;
@@ -13,6 +13,8 @@ pub enum ResoPbMapError {
     UnsetOneOfType,
     #[error(transparent)]
     IntegerOverflow(#[from] TryFromIntError),
+    #[error("GRPC member json could not be parsed: {0}")]
+    InvalidJson(#[from] serde_json::Error),
 }
 
 impl From<ResoPbMapError> for Status {
@@ -185,6 +187,10 @@ impl From<data_model::Member> for Member {
                 Int4(f) => PB::Int4(f.into()),
                 Float2(f) => PB::Float2(f.into()),
                 Float4(f) => PB::Float4(f.into()),
+                other => PB::Json(FieldJson {
+                    id: other.id().to_owned(),
+                    json: serde_json::to_string(&other).expect("members always serialize to json"),
+                }),
             }),
         }
     }
@@ -234,6 +240,7 @@ impl TryFrom<Member> for data_model::Member {
                 ColorX(f) => DM::ColorX(f.try_into()?),
                 NullColorX(f) => DM::NullColorX(f.try_into()?),
                 Color32(f) => DM::Color32(f.try_into()?),
+                Json(f) => serde_json::from_str(&f.json)?,
             })
         } else {
             Err(ResoPbMapError::UnsetOneOfType)
@@ -285,6 +292,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn members_without_messages_use_json() {
+        let src = Member::Double3(Field::new(
+            "taco",
+            Double3 {
+                x: 1.,
+                y: 2.,
+                z: 3.,
+            },
+        ));
+        let mid: super::Member = src.clone().into();
+        assert!(matches!(
+            &mid.member_kind,
+            Some(super::member::MemberKind::Json(f)) if f.id == "taco"
+        ));
+        let output: Member = mid.try_into().unwrap();
+        assert_eq!(output, src);
+    }
+
     #[test]
     fn test_bidirectional() {
         let src = make_test_slot();
@@ -4,6 +4,7 @@ use crate::pb::{
     GetSlotRequest, RemoveSlotRequest, SlotResponse,
 };
 use resonite_link_client::responses::ResponseKind;
+use resonite_link_client::{ClientError, RemoteErrorKind};
 use tonic::{Code, Request, Response, Status};
 
 pub struct LinkProxy {
@@ -19,22 +20,25 @@ impl LinkProxy {
         &self,
         req: resonite_link_client::Message,
     ) -> Result<resonite_link_client::Response, Status> {
-        let response = self
-            .client
-            .send(req)
-            .await
-            .map_err(|e| Status::new(Code::Internal, format!("{:?}", e)))?;
-
-        if !response.success {
-            return Err(Status::new(
-                Code::InvalidArgument,
-                response.error_info.unwrap_or_else(|| {
-                    "Resonite said there was an error, but did not provide an error message.".into()
-                }),
-            ));
-        }
-
-        Ok(response)
+        self.client.send(req).await.map_err(|e| match e {
+            ClientError::Remote(remote) => {
+                let code = match remote.kind {
+                    RemoteErrorKind::NotFound => Code::NotFound,
+                    RemoteErrorKind::Permission => Code::PermissionDenied,
+                    RemoteErrorKind::InvalidType | RemoteErrorKind::Unknown => {
+                        Code::InvalidArgument
+                    }
+                };
+                Status::new(
+                    code,
+                    remote.error_info.unwrap_or_else(|| {
+                        "Resonite said there was an error, but did not provide an error message."
+                            .into()
+                    }),
+                )
+            }
+            e => Status::new(Code::Internal, format!("{:?}", e)),
+        })
     }
 
     async fn proxy_slot_req(
@@ -103,7 +107,8 @@ impl ResoniteLink for LinkProxy {
     ) -> Result<Response<SlotResponse>, Status> {
         let request = request.into_inner();
         if let Some(slot) = request.data {
-            self.proxy_slot_req(resonite_link_client::Message::UpdateSlot { data: slot.try_into()? })
+            let slot: resonite_link_client::data_model::Slot = slot.try_into()?;
+            self.proxy_slot_req(resonite_link_client::Message::UpdateSlot { data: slot.into() })
                 .await
         } else {
             Err(Status::new(Code::InvalidArgument, "data is required."))
@@ -154,8 +159,9 @@ impl ResoniteLink for LinkProxy {
     ) -> Result<Response<ComponentResponse>, Status> {
         let request = request.into_inner();
         if let Some(component) = request.data {
+            let component: resonite_link_client::data_model::Component = component.try_into()?;
             self.proxy_component_req(resonite_link_client::Message::UpdateComponent {
-                data: component.try_into()?,
+                data: component.into(),
             })
             .await
         } else {
@@ -174,3 +180,49 @@ impl ResoniteLink for LinkProxy {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use resonite_link_client::test_support::{Simulator, SimulatorServer};
+
+    async fn proxy() -> (SimulatorServer, LinkProxy) {
+        let server = Simulator::default().listen("127.0.0.1:0").await.unwrap();
+        let client = resonite_link_client::Client::connect(&server.url(), None)
+            .await
+            .unwrap();
+        (server, LinkProxy::new(client))
+    }
+
+    #[tokio::test]
+    async fn proxies_slots() {
+        let (_server, proxy) = proxy().await;
+
+        let root = proxy
+            .get_slot(Request::new(GetSlotRequest {
+                slot_id: "Root".into(),
+                depth: 0,
+                include_component_data: false,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(root.data.unwrap().id, "Root");
+    }
+
+    #[tokio::test]
+    async fn maps_remote_errors_to_codes() {
+        let (_server, proxy) = proxy().await;
+
+        let status = proxy
+            .get_component(Request::new(ComponentIdRequest {
+                component_id: "Taco".into(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), Code::NotFound);
+    }
+}
+